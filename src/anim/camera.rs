@@ -0,0 +1,88 @@
+use egui::{emath::RectTransform, Id, Rect, Response, Ui, Vec2};
+
+const MAX_ZOOM: f32 = 8.0;
+/// Zoom factor per pixel scrolled with the mouse wheel
+const SCROLL_ZOOM_SPEED: f32 = 1.0 / 200.0;
+
+/// Zoom and pan of the field. Kept in egui memory, since it is part of the
+/// editor and not of the play.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    /// 1 shows the whole field
+    zoom: f32,
+    /// Offset of the center of the screen from the center of the field, in meters
+    pan: Vec2,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            pan: Vec2::ZERO,
+        }
+    }
+}
+
+impl Camera {
+    pub fn load(ui: &Ui, id: Id) -> Self {
+        ui.data(|data| data.get_temp(id)).unwrap_or_default()
+    }
+
+    pub fn store(self, ui: &Ui, id: Id) {
+        ui.data_mut(|data| data.insert_temp(id, self));
+    }
+
+    pub fn is_fit(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Maps meters inside `bounds` to the screen `rect`
+    pub fn transform(&self, bounds: Rect, rect: Rect) -> RectTransform {
+        let visible = Rect::from_center_size(bounds.center() + self.pan, bounds.size() / self.zoom);
+        RectTransform::from_to(visible, rect)
+    }
+
+    /// Zooms with the mouse wheel or by pinching, and pans by dragging with the
+    /// middle mouse button or two fingers, while the pointer is over `response`
+    pub fn handle_input(&mut self, ui: &Ui, response: &Response, bounds: Rect) {
+        if !response.hovered() {
+            return;
+        }
+        let rect = response.rect;
+        let (scroll, zoom_delta, middle_drag, touch_pan, pointer) = ui.input(|i| {
+            let middle_drag = if i.pointer.middle_down() {
+                i.pointer.delta()
+            } else {
+                Vec2::ZERO
+            };
+            let touch_pan = i
+                .multi_touch()
+                .map_or(Vec2::ZERO, |touch| touch.translation_delta);
+            (
+                i.smooth_scroll_delta.y,
+                i.zoom_delta(),
+                middle_drag,
+                touch_pan,
+                i.pointer.hover_pos(),
+            )
+        });
+
+        let to_screen = self.transform(bounds, rect);
+        self.pan -= (middle_drag + touch_pan) / to_screen.scale();
+
+        let factor = zoom_delta * (scroll * SCROLL_ZOOM_SPEED).exp();
+        if factor != 1.0 {
+            // Keep the point under the pointer in place
+            let anchor = pointer.unwrap_or(rect.center());
+            let field_anchor = to_screen.inverse().transform_pos(anchor);
+            self.zoom = (self.zoom * factor).clamp(1.0, MAX_ZOOM);
+            let to_screen = self.transform(bounds, rect);
+            let moved = to_screen.inverse().transform_pos(anchor) - field_anchor;
+            self.pan -= moved;
+        }
+
+        // Never let the field leave the screen
+        let max_pan = bounds.size() * (1.0 - 1.0 / self.zoom) / 2.0;
+        self.pan = self.pan.clamp(-max_pan, max_pan);
+    }
+}
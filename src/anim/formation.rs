@@ -0,0 +1,143 @@
+//! Lining up teams in the editor, and the formations the user saved.
+
+use egui::Ui;
+
+use crate::model::{
+    formation::{Formation, Team},
+    save::MAX_LABEL_LEN,
+    Path,
+};
+
+use super::Animation;
+
+/// Longest name of a saved formation
+const MAX_NAME_LEN: usize = 32;
+
+impl Animation<'_> {
+    /// Moves the team of `formation` into it at the start of the current step,
+    /// where they stand still. Players are added or removed so that the team
+    /// has one player per spot.
+    pub fn apply_formation(&mut self, formation: &Formation) {
+        let p_type = formation.team.p_type();
+        let mut selected = self.editor.selected;
+        let mut team: Vec<usize> = self.play.steps[self.editor.cur_frame]
+            .actors
+            .iter()
+            .enumerate()
+            .filter(|(_, actor)| actor.p_type == p_type)
+            .map(|(idx, _)| idx)
+            .collect();
+
+        // Remove from the back, so the indices before stay valid
+        while team.len() > formation.spots.len() {
+            let idx = team.pop().expect("team is not empty");
+            self.remove_person(idx);
+            // Everyone after it moved
+            selected = None;
+        }
+        while team.len() < formation.spots.len() {
+            let spot = &formation.spots[team.len()];
+            self.add_person(spot.pt, p_type);
+            team.push(self.play.steps[0].actors.len() - 1);
+        }
+
+        for (&idx, spot) in team.iter().zip(&formation.spots) {
+            let label: String = spot.label.chars().take(MAX_LABEL_LEN).collect();
+            for frame in self.play.steps.iter_mut() {
+                frame.actors[idx].label = label.clone();
+            }
+            self.play.steps[self.editor.cur_frame].actors[idx].path = Path::None(spot.pt);
+        }
+
+        self.editor.selected = selected;
+        if self.play.linked {
+            self.play.unlink_moved(self.editor.cur_frame);
+            self.play.relink(self.editor.cur_frame + 1);
+        }
+    }
+}
+
+/// The formations the user has saved
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Formations {
+    pub custom: Vec<Formation>,
+
+    /// Name to save the next formation under
+    #[serde(skip)]
+    new_name: String,
+}
+
+impl Formations {
+    /// Menu to line up a team in a formation, or save how it stands now.
+    /// Returns a description of the edit if a formation was applied.
+    pub fn display(&mut self, ui: &mut Ui, animation: &mut Animation<'_>) -> Option<String> {
+        let mut edit = None;
+        let mut delete = None;
+
+        ui.menu_button("Formation", |ui| {
+            for team in Team::ALL {
+                ui.menu_button(team.name(), |ui| {
+                    let presets = Formation::presets();
+                    let formations = presets
+                        .iter()
+                        .chain(&self.custom)
+                        .enumerate()
+                        .filter(|(_, formation)| formation.team == team);
+                    for (idx, formation) in formations {
+                        let custom = idx.checked_sub(presets.len());
+                        ui.horizontal(|ui| {
+                            if ui.button(&formation.name).clicked() {
+                                animation.apply_formation(formation);
+                                edit = Some(format!(
+                                    "Line up {} in {}",
+                                    team.name().to_lowercase(),
+                                    formation.name
+                                ));
+                                ui.close_menu();
+                            }
+                            if custom.is_some()
+                                && ui.small_button("🗑").on_hover_text("Delete").clicked()
+                            {
+                                delete = custom;
+                            }
+                        });
+                    }
+                });
+            }
+
+            ui.separator();
+            ui.label("Save how a team stands now");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.new_name)
+                    .char_limit(MAX_NAME_LEN)
+                    .hint_text("Name"),
+            );
+            ui.horizontal(|ui| {
+                for team in Team::ALL {
+                    let name = self.new_name.trim();
+                    if ui
+                        .add_enabled(
+                            !name.is_empty(),
+                            egui::Button::new(format!("Save {}", team.name().to_lowercase())),
+                        )
+                        .clicked()
+                    {
+                        self.custom.push(Formation::from_step(
+                            name,
+                            team,
+                            &animation.play.steps[animation.editor.cur_frame],
+                        ));
+                        self.new_name.clear();
+                        ui.close_menu();
+                    }
+                }
+            });
+        });
+
+        if let Some(idx) = delete {
+            self.custom.remove(idx);
+        }
+        edit
+    }
+}
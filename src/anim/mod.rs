@@ -1,56 +1,553 @@
+//! The editor for plays, drawn with egui.
+
+pub mod camera;
 pub mod field;
+pub mod formation;
 pub mod person;
 pub mod util;
 
-use egui::{Ui, Vec2};
+use egui::{
+    emath::RectTransform, Align2, Color32, DragValue, FontId, Rect, Shape, Stroke, Ui, Vec2,
+};
+
+use crate::model::{
+    ball::{goal_posts, Flight, GoalTarget, Pass, Shot},
+    field::{FieldView, GOAL_HEIGHT, GOAL_WIDTH, WIDTH},
+    save, Easing, Path, Play, PlayerType, Point, Step, DURATION_RANGE,
+};
 
 use self::{
+    camera::Camera,
     field::draw_field,
-    person::{Movement, Person},
+    person::{format_speed, ActorUi},
+    util::get_screen_coords,
 };
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
-pub struct Animation {
-    pub frames: Vec<Vec<Person>>,
+/// Where the user is in the play being edited. Kept apart from the play, which
+/// is saved without it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EditorState {
     pub cur_frame: usize,
+    /// Index of the selected person, the same in every step
+    pub selected: Option<usize>,
+}
+
+/// A play being edited
+pub struct Animation<'a> {
+    pub play: &'a mut Play,
+    pub editor: &'a mut EditorState,
 }
 
-impl Animation {
-    pub fn new(frame: Vec<Person>) -> Self {
-        Self {
-            frames: vec![frame],
-            cur_frame: 0,
+impl Animation<'_> {
+    pub fn add_frame(&mut self) {
+        self.insert_frame(self.play.steps.len());
+    }
+
+    /// Inserts a step at `idx` where everyone stands still where they ended
+    /// in the step before, and shows it
+    pub fn insert_frame(&mut self, idx: usize) {
+        self.play.insert_step(idx);
+        self.editor.cur_frame = idx;
+    }
+
+    /// Inserts a copy of step `idx` after it, and shows the copy
+    pub fn duplicate_frame(&mut self, idx: usize) {
+        self.play.duplicate_step(idx);
+        self.editor.cur_frame = idx + 1;
+    }
+
+    /// Deletes step `idx`, unless it is the only one
+    pub fn delete_frame(&mut self, idx: usize) {
+        if self.play.steps.len() == 1 {
+            return;
+        }
+        self.play.delete_step(idx);
+        if self.editor.cur_frame >= idx && self.editor.cur_frame > 0 {
+            self.editor.cur_frame -= 1;
         }
     }
 
-    pub fn add_frame(&mut self) {
-        let mut new_frame = Vec::new();
+    /// Moves step `from` so that it becomes step `to`, and shows it
+    pub fn move_frame(&mut self, from: usize, to: usize) {
+        self.play.move_step(from, to);
+        self.editor.cur_frame = to;
+    }
+
+    /// Adds a person standing still at `pt` in every step, and selects it
+    pub fn add_person(&mut self, pt: Point, p_type: PlayerType) {
+        self.play.add_actor(pt, p_type);
+        self.editor.selected = Some(self.play.steps[0].actors.len() - 1);
+    }
+
+    /// Removes person `idx` from every step
+    pub fn remove_person(&mut self, idx: usize) {
+        self.play.remove_actor(idx);
+        self.editor.selected = None;
+    }
+
+    /// Where to put a new person of type `p_type`, so that it is on the field
+    fn spawn_point(&self, p_type: PlayerType) -> Point {
+        let bottom = self.play.view.bounds().max.y - 2.0;
+        let x = WIDTH / 2.0;
+        match p_type {
+            PlayerType::DefendingGoalkeeper => Point::new(x, 1.0),
+            PlayerType::AttackingGoalkeeper => Point::new(x, bottom),
+            // Referees usually stand by the sideline
+            PlayerType::Referee => Point::new(WIDTH - 1.0, 10.0),
+            // Close to the goal, where most of the action is
+            _ => Point::new(x, 10.0),
+        }
+    }
+
+    /// Index of the ball in the current step, if someone has it so that it
+    /// cannot be moved by itself
+    fn held_ball(&self) -> Option<usize> {
+        let frame = &self.play.steps[self.editor.cur_frame];
+        frame.carrier.and(frame.ball())
+    }
+
+    /// Marks players that are unlinked from the previous step
+    fn draw_unlinked(&self, ui: &mut Ui, to_screen: RectTransform) {
+        if self.editor.cur_frame == 0 {
+            return;
+        }
+        let prev_frame = self.play.steps[self.editor.cur_frame - 1].actors.iter();
+        let stroke = Stroke::new(1.5_f32, Color32::GOLD);
+        for (prev, player) in prev_frame.zip(self.play.steps[self.editor.cur_frame].actors.iter()) {
+            if !player.unlinked {
+                continue;
+            }
+            let start = get_screen_coords(player.path.start(), to_screen);
+            let prev_end = get_screen_coords(prev.path.end(), to_screen);
+            ui.painter()
+                .extend(Shape::dashed_line(&[prev_end, start], stroke, 4.0, 4.0));
+            ui.painter().text(
+                start + Vec2::new(8.0, -8.0),
+                Align2::LEFT_BOTTOM,
+                "⚠",
+                FontId::proportional(16.0),
+                Color32::GOLD,
+            );
+        }
+    }
+
+    /// Toolbar to add people, and fields to edit the selected person.
+    /// Returns a description of the edit when one is finished.
+    pub fn display_players(&mut self, ui: &mut Ui) -> Option<String> {
+        let mut edit = None;
+        let mut remove = false;
+
+        ui.horizontal(|ui| {
+            let mut add = None;
+            ui.label("Add");
+            for p_type in PlayerType::PEOPLE {
+                if ui.button(p_type.name()).clicked() {
+                    add = Some(p_type);
+                }
+            }
+            ui.menu_button("equipment", |ui| {
+                for p_type in PlayerType::EQUIPMENT {
+                    if ui.button(p_type.name()).clicked() {
+                        add = Some(p_type);
+                        ui.close_menu();
+                    }
+                }
+            });
+            if let Some(p_type) = add {
+                self.add_person(self.spawn_point(p_type), p_type);
+                edit = Some(format!("Add {}", p_type.name()));
+            }
+
+            let num_persons = self.play.steps[0].actors.len();
+            let Some(idx) = self.editor.selected.filter(|&idx| idx < num_persons) else {
+                return;
+            };
+            ui.separator();
 
-        for player in self.frames[self.frames.len() - 1].iter() {
-            let pos = match player.movement {
-                Movement::None(pos) => pos,
-                Movement::Bezier(pts) => pts[3],
+            // The same person in every step, so all of them are edited together
+            let person = &self.play.steps[self.editor.cur_frame].actors[idx];
+            let (mut label, mut number, mut player_name) = (
+                person.label.clone(),
+                person.number,
+                person.player_name.clone(),
+            );
+
+            ui.label("Label");
+            let label_response = ui.add(
+                egui::TextEdit::singleline(&mut label)
+                    .char_limit(save::MAX_LABEL_LEN)
+                    .desired_width(40.0),
+            );
+
+            ui.label("#");
+            let mut has_number = number.is_some();
+            let number_toggle = ui.checkbox(&mut has_number, "");
+            if number_toggle.changed() {
+                number = has_number.then_some(number.unwrap_or(1));
+            }
+            let number_response = number
+                .as_mut()
+                .map(|number| ui.add(DragValue::new(number).clamp_range(0..=save::MAX_NUMBER)));
+
+            ui.label("Name");
+            let name_response = ui.add(
+                egui::TextEdit::singleline(&mut player_name)
+                    .char_limit(save::MAX_NAME_LEN)
+                    .desired_width(120.0),
+            );
+
+            for frame in self.play.steps.iter_mut() {
+                let person = &mut frame.actors[idx];
+                person.label.clone_from(&label);
+                person.number = number;
+                person.player_name.clone_from(&player_name);
+            }
+
+            let finished =
+                |response: &egui::Response| response.lost_focus() || response.drag_released();
+            if finished(&label_response)
+                || finished(&name_response)
+                || number_response.as_ref().is_some_and(finished)
+                || number_toggle.changed()
+            {
+                edit = Some(format!("Edit {}", self.play.steps[0].actors[idx].name()));
+            }
+
+            if ui.button("Delete").clicked() {
+                remove = true;
+            }
+        });
+
+        if remove {
+            if let Some(idx) = self.editor.selected {
+                edit = Some(format!("Delete {}", self.play.steps[0].actors[idx].name()));
+                self.remove_person(idx);
+            }
+        }
+
+        edit
+    }
+
+    /// Lets the user choose how much of the court to show
+    pub fn display_view(&mut self, ui: &mut Ui) -> Option<String> {
+        let mut view = self.play.view;
+        egui::ComboBox::from_id_source("field view")
+            .selected_text(view.name())
+            .show_ui(ui, |ui| {
+                for option in FieldView::ALL {
+                    ui.selectable_value(&mut view, option, option.name());
+                }
+            });
+        if view == self.play.view {
+            return None;
+        }
+        self.play.view = view;
+        Some(format!("Show {}", view.name().to_lowercase()))
+    }
+
+    /// Edits the duration of the current step, and when the selected person
+    /// moves during it. Returns a description of the edit when one is finished.
+    pub fn display_timing(&mut self, ui: &mut Ui) -> Option<String> {
+        let mut edit = None;
+        let mut set_smooth = None;
+        let step = self.editor.cur_frame + 1;
+        let frame = &mut self.play.steps[self.editor.cur_frame];
+
+        ui.horizontal(|ui| {
+            ui.label(format!("Step {step} lasts"));
+            let response = ui.add(
+                DragValue::new(&mut frame.duration)
+                    .clamp_range(DURATION_RANGE)
+                    .speed(0.05)
+                    .fixed_decimals(2)
+                    .suffix(" s"),
+            );
+            if response.drag_released() || response.lost_focus() {
+                edit = Some(format!("Change duration of step {step}"));
+            }
+
+            let Some(person) = self
+                .editor
+                .selected
+                .and_then(|idx| frame.actors.get_mut(idx))
+            else {
+                ui.weak("Select a player to change when it moves");
+                return;
             };
-            new_frame.push(Person::new(
-                Movement::None(pos),
-                &player.label,
-                player.p_type,
-            ));
+            if !person.p_type.can_move() {
+                return;
+            }
+
+            ui.separator();
+            ui.label(format!("{} moves from", person.name()));
+            let duration = frame.duration;
+            let mut start = person.timing.start * duration;
+            let mut end = person.timing.end * duration;
+            let start_response = ui.add(
+                DragValue::new(&mut start)
+                    .clamp_range(0.0..=end)
+                    .speed(0.05)
+                    .fixed_decimals(2)
+                    .suffix(" s"),
+            );
+            ui.label("to");
+            let end_response = ui.add(
+                DragValue::new(&mut end)
+                    .clamp_range(start..=duration)
+                    .speed(0.05)
+                    .fixed_decimals(2)
+                    .suffix(" s"),
+            );
+            if start_response.changed() {
+                person.timing.start = (start / duration).clamp(0.0, person.timing.end);
+            }
+            if end_response.changed() {
+                person.timing.end = (end / duration).clamp(person.timing.start, 1.0);
+            }
+
+            if [start_response, end_response]
+                .iter()
+                .any(|response| response.drag_released() || response.lost_focus())
+            {
+                edit = Some(format!("Change timing of {}", person.name()));
+            }
+
+            let mut easing = person.timing.easing;
+            egui::ComboBox::from_id_source("easing")
+                .selected_text(easing.name())
+                .show_ui(ui, |ui| {
+                    for option in Easing::ALL {
+                        ui.selectable_value(&mut easing, option, option.name());
+                    }
+                });
+            if easing != person.timing.easing {
+                person.timing.easing = easing;
+                edit = Some(format!("Change speed profile of {}", person.name()));
+            }
+
+            if person.path.length() > 0.0 {
+                let average = person.average_speed(duration);
+                let top = average * easing.top_speed();
+                ui.label(format!(
+                    "Average {}, top {}",
+                    format_speed(average),
+                    format_speed(top)
+                ));
+            }
+
+            let mut smooth = person.smooth;
+            if ui
+                .checkbox(&mut smooth, "Smooth across steps")
+                .on_hover_text("Start every step in the direction the previous step ended in")
+                .changed()
+            {
+                set_smooth = Some(smooth);
+                edit = Some(format!("Toggle smooth turns of {}", person.name()));
+            }
+        });
+
+        if let (Some(smooth), Some(idx)) = (set_smooth, self.editor.selected) {
+            self.play.set_smooth(idx, smooth);
         }
 
-        self.frames.push(new_frame);
-        self.cur_frame = self.frames.len() - 1;
+        edit
+    }
+
+    /// Coach notes for the current step. Returns a description of the edit
+    /// when one is finished.
+    pub fn display_notes(&mut self, ui: &mut Ui) -> Option<String> {
+        let step = self.editor.cur_frame + 1;
+        let frame = &mut self.play.steps[self.editor.cur_frame];
+        let response = egui::CollapsingHeader::new("Coach notes")
+            .id_source("coach notes")
+            .show(ui, |ui| {
+                ui.add(
+                    egui::TextEdit::multiline(&mut frame.notes)
+                        .desired_rows(2)
+                        .hint_text(format!("What to look for in step {step}")),
+                )
+            });
+        let edited = response.body_returned?;
+        edited
+            .lost_focus()
+            .then(|| format!("Edit notes of step {step}"))
     }
 
-    pub fn display(&mut self, ui: &mut Ui, animation_time: Option<f32>) {
+    /// Who has the ball when the step starts, and the passes during it.
+    /// Returns a description of the edit if the user edited the passes.
+    pub fn display_passes(&mut self, ui: &mut Ui) -> Option<String> {
+        let mut edit = None;
+        let step = self.editor.cur_frame + 1;
+        // The ball can only be handed out where the previous step did not end with someone holding it
+        let can_give = self.editor.cur_frame == 0
+            || self.play.steps[self.editor.cur_frame - 1]
+                .final_carrier()
+                .is_none();
+        let frame = &mut self.play.steps[self.editor.cur_frame];
+        // Nothing to pass without a ball
+        frame.ball()?;
+        let selected = self.editor.selected.filter(|&idx| {
+            frame
+                .actors
+                .get(idx)
+                .is_some_and(|p| p.p_type.can_hold_ball())
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Ball:");
+            match frame.carrier {
+                Some(carrier) => ui.label(format!("{} has it", frame.actors[carrier].name())),
+                None => ui.weak("nobody has it"),
+            };
+
+            if let Some(idx) = selected.filter(|&idx| can_give && frame.carrier != Some(idx)) {
+                let name = frame.actors[idx].name();
+                if ui.button(format!("Give ball to {name}")).clicked() {
+                    frame.carrier = Some(idx);
+                    frame.passes.clear();
+                    edit = Some(format!("Give ball to {name} in step {step}"));
+                }
+            }
+            if can_give && frame.carrier.is_some() && ui.button("Drop ball").clicked() {
+                // Leave it where it was held
+                if let (Some(ball), Some((pt, _))) = (frame.ball(), frame.ball_at(0.0)) {
+                    frame.actors[ball].path = Path::None(pt);
+                }
+                frame.carrier = None;
+                frame.passes.clear();
+                edit = Some(format!("Drop ball in step {step}"));
+            }
+
+            let holder = frame.final_carrier();
+            if let (Some(from), Some(to)) = (holder, selected.filter(|&idx| holder != Some(idx))) {
+                let name = frame.actors[to].name();
+                if ui.button(format!("Pass to {name}")).clicked() {
+                    let throw = frame.passes.last().map_or(0.0, |pass| pass.catch);
+                    frame.passes.push(Pass {
+                        from,
+                        to,
+                        throw,
+                        catch: (throw + 0.3).min(1.0),
+                        flight: Flight::default(),
+                    });
+                    edit = Some(format!("Pass to {name} in step {step}"));
+                }
+            }
+        });
+
+        let duration = frame.duration;
+        let mut delete = None;
+        for idx in 0..frame.passes.len() {
+            let earliest = if idx == 0 {
+                0.0
+            } else {
+                frame.passes[idx - 1].catch
+            };
+            let latest = match (frame.passes.get(idx + 1), &frame.shot) {
+                (Some(next), _) => next.throw,
+                (None, Some(shot)) => shot.time,
+                (None, None) => 1.0,
+            };
+            let persons = &frame.actors;
+            let pass = &mut frame.passes[idx];
+            let from_to = format!(
+                "from {} to {}",
+                persons[pass.from].name(),
+                persons[pass.to].name()
+            );
+
+            ui.horizontal(|ui| {
+                ui.label(format!("Pass {from_to}, thrown at"));
+                let mut throw = pass.throw * duration;
+                let mut catch = pass.catch * duration;
+                let throw_response = ui.add(
+                    DragValue::new(&mut throw)
+                        .clamp_range(earliest * duration..=catch)
+                        .speed(0.05)
+                        .fixed_decimals(2)
+                        .suffix(" s"),
+                );
+                ui.label("caught at");
+                let catch_response = ui.add(
+                    DragValue::new(&mut catch)
+                        .clamp_range(throw..=latest * duration)
+                        .speed(0.05)
+                        .fixed_decimals(2)
+                        .suffix(" s"),
+                );
+                if throw_response.changed() {
+                    pass.throw = (throw / duration).clamp(earliest, pass.catch);
+                }
+                if catch_response.changed() {
+                    pass.catch = (catch / duration).clamp(pass.throw, latest);
+                }
+                if [throw_response, catch_response]
+                    .iter()
+                    .any(|response| response.drag_released() || response.lost_focus())
+                {
+                    edit = Some(format!("Change timing of pass {from_to}"));
+                }
+
+                let mut flight = pass.flight;
+                egui::ComboBox::from_id_source(("flight", idx))
+                    .selected_text(flight.name())
+                    .show_ui(ui, |ui| {
+                        for option in Flight::ALL {
+                            ui.selectable_value(&mut flight, option, option.name());
+                        }
+                    });
+                if flight != pass.flight {
+                    pass.flight = flight;
+                    edit = Some(format!("Change flight of pass {from_to}"));
+                }
+
+                if ui
+                    .button("🗑")
+                    .on_hover_text("Delete this pass and everything after it")
+                    .clicked()
+                {
+                    delete = Some(idx);
+                    edit = Some(format!("Delete pass {from_to}"));
+                }
+            });
+        }
+        if let Some(idx) = delete {
+            frame.passes.truncate(idx);
+            frame.shot = None;
+        }
+
+        if let Some(shot_edit) = display_shot(ui, frame) {
+            edit = Some(shot_edit);
+        }
+
+        if edit.is_some() {
+            self.play.pass_on_possession();
+        }
+        edit
+    }
+
+    /// Returns a description of the edit if the user edited the animation
+    pub fn display(
+        &mut self,
+        ui: &mut Ui,
+        animation_time: Option<f32>,
+        editable: bool,
+        show_speeds: bool,
+    ) -> Option<String> {
+        let bounds = self.play.view.bounds();
+        let aspect = bounds.aspect_ratio();
         let a_size = ui.available_size();
-        let a_width = a_size.x.min(a_size.y - 100.0);
+        let a_width = a_size.x.min((a_size.y - 100.0) * aspect);
         let desired_size = Vec2 {
             x: a_width,
-            y: a_width,
+            y: a_width / aspect,
         };
 
-        let (rect, _response) = ui.allocate_exact_size(desired_size, egui::Sense::hover());
+        let (rect, response) = ui.allocate_exact_size(desired_size, egui::Sense::hover());
+        let camera_id = ui.id().with("camera");
+        let mut camera = Camera::load(ui, camera_id);
+        camera.handle_input(ui, &response, bounds);
+        let to_screen = camera.transform(bounds, rect);
 
         ui.label(format!(
             "x {} {}, y {} {}",
@@ -59,26 +556,284 @@ impl Animation {
 
         ui.separator();
 
+        let mut edit = None;
+
         // Draw handball field
         if ui.is_rect_visible(rect) {
-            draw_field(ui, rect);
+            // Nothing outside the field can be seen or clicked when zoomed in
+            let clip_rect = ui.clip_rect();
+            ui.set_clip_rect(rect.intersect(clip_rect));
+
+            draw_field(ui, to_screen, self.play.view);
 
             match animation_time {
+                None if editable => {
+                    let held_ball = self.held_ball();
+                    for i in 0..self.play.steps[self.editor.cur_frame].actors.len() {
+                        if Some(i) == held_ball {
+                            continue;
+                        }
+                        // The same person in every step, so its widgets keep their state
+                        let id = ui.id().with(("person", i));
+                        let p = &mut self.play.steps[self.editor.cur_frame].actors[i];
+                        let was_unlinked = p.unlinked;
+                        let (response, person_edit) =
+                            p.display(ui, to_screen, id, self.editor.selected == Some(i));
+                        if was_unlinked && !p.unlinked {
+                            self.play.link_actor(self.editor.cur_frame, i);
+                        }
+                        if response.clicked() || response.drag_started() {
+                            self.editor.selected = Some(i);
+                        }
+                        if person_edit.is_some() {
+                            edit = person_edit;
+                            if !self.play.steps[self.editor.cur_frame].actors[i]
+                                .p_type
+                                .can_move()
+                            {
+                                self.play.place_in_every_step(i, self.editor.cur_frame);
+                            }
+                        }
+                    }
+                    if self.play.linked {
+                        if edit.is_some() {
+                            self.play.unlink_moved(self.editor.cur_frame);
+                            self.play.relink(self.editor.cur_frame + 1);
+                        }
+                        self.draw_unlinked(ui, to_screen);
+                    }
+                    // Also while dragging, so the user sees the path being turned
+                    if edit.is_some() || ui.memory(|mem| mem.is_anything_being_dragged()) {
+                        self.play.smooth_joins();
+                    }
+                }
                 None => {
-                    for i in 0..self.frames[self.cur_frame].len() {
-                        let p = &mut self.frames[self.cur_frame][i];
-                        p.display(ui, rect);
+                    let held_ball = self.held_ball();
+                    for (i, p) in self.play.steps[self.editor.cur_frame]
+                        .actors
+                        .iter()
+                        .enumerate()
+                    {
+                        if Some(i) != held_ball {
+                            p.draw(ui, to_screen);
+                        }
                     }
                 }
                 Some(time) => {
-                    let timestep = time.floor();
-                    let frac = time - timestep;
-                    let frameidx = (timestep as usize).min(self.frames.len() - 1);
-                    for p in self.frames[frameidx].iter() {
-                        p.animate(ui, rect, frac);
+                    let (frameidx, frac) = self.play.step_at(time);
+                    let frame = &self.play.steps[frameidx];
+                    let positions = frame.positions(frac);
+                    let ball = frame.ball().zip(frame.ball_at(frac));
+                    for (i, p) in frame.actors.iter().enumerate() {
+                        match ball {
+                            Some((ball, (pt, height))) if ball == i => {
+                                p.animate_in_air(ui, to_screen, pt, height);
+                            }
+                            _ => p.animate(ui, to_screen, positions[i]),
+                        }
+                        if show_speeds && p.path.length() > 0.0 {
+                            let speed = p.speed_at(frac, frame.duration);
+                            p.draw_speed(ui, to_screen, positions[i], speed);
+                        }
                     }
                 }
             }
+
+            if animation_time.is_none() {
+                let frame = &self.play.steps[self.editor.cur_frame];
+                draw_passes(ui, to_screen, frame);
+                draw_shot(ui, to_screen, frame);
+
+                // Average speeds of the step being edited
+                if show_speeds {
+                    for p in frame.actors.iter() {
+                        if p.path.length() > 0.0 {
+                            let speed = p.average_speed(frame.duration);
+                            p.draw_speed(ui, to_screen, p.path.start(), speed);
+                        }
+                    }
+                }
+            }
+
+            if !camera.is_fit() {
+                let button_rect =
+                    Rect::from_min_size(rect.min + Vec2::splat(4.0), Vec2::new(90.0, 20.0));
+                if ui
+                    .put(button_rect, egui::Button::new("Fit to field"))
+                    .on_hover_text("Reset zoom and pan")
+                    .clicked()
+                {
+                    camera = Camera::default();
+                }
+            }
+
+            ui.set_clip_rect(clip_rect);
+        }
+
+        camera.store(ui, camera_id);
+
+        edit
+    }
+}
+
+/// Draws the ball held by its carrier, and arrows for the passes of the step
+fn draw_passes(ui: &mut Ui, to_screen: RectTransform, frame: &Step) {
+    if let (Some(ball), Some((pt, height))) = (frame.ball(), frame.ball_at(0.0)) {
+        frame.actors[ball].animate_in_air(ui, to_screen, pt, height);
+    }
+
+    let stroke = Stroke::new(2.0_f32, PlayerType::Ball.color());
+    for (from, to, flight) in frame.pass_lines() {
+        let from = get_screen_coords(from, to_screen);
+        let to = get_screen_coords(to, to_screen);
+        match flight {
+            Flight::Straight => {
+                ui.painter().arrow(from, to - from, stroke);
+            }
+            Flight::Bounce => {
+                // Where the ball hits the floor
+                let bounce = from.lerp(to, 0.6);
+                ui.painter().line_segment([from, bounce], stroke);
+                ui.painter().arrow(bounce, to - bounce, stroke);
+                ui.painter().circle_stroke(bounce, 3.0, stroke);
+            }
+            Flight::Lob => {
+                ui.painter()
+                    .extend(Shape::dashed_line(&[from, to], stroke, 6.0, 4.0));
+                ui.painter().arrow(
+                    to - (to - from).normalized() * 12.0,
+                    (to - from).normalized() * 12.0,
+                    stroke,
+                );
+            }
+        }
+    }
+}
+
+/// Lets the player that has the ball after the passes shoot, and edits the shot.
+/// Returns a description of the edit if the user edited the shot.
+fn display_shot(ui: &mut Ui, frame: &mut Step) -> Option<String> {
+    let mut edit = None;
+    let shooter = frame.actors[frame.holder()?].name();
+    let earliest = frame.passes.last().map_or(0.0, |pass| pass.catch);
+
+    ui.horizontal(|ui| {
+        let Some(shot) = &mut frame.shot else {
+            if ui.button(format!("{shooter} shoots")).clicked() {
+                frame.shot = Some(Shot {
+                    time: earliest,
+                    target: GoalTarget::default(),
+                    reaction: None,
+                });
+                edit = Some(format!("Add shot from {shooter}"));
+            }
+            return;
+        };
+
+        let duration = frame.duration;
+        ui.label(format!("{shooter} shoots at"));
+        let mut time = shot.time * duration;
+        let response = ui.add(
+            DragValue::new(&mut time)
+                .clamp_range(earliest * duration..=duration)
+                .speed(0.05)
+                .fixed_decimals(2)
+                .suffix(" s"),
+        );
+        if response.changed() {
+            shot.time = (time / duration).clamp(earliest, 1.0);
         }
+        if response.drag_released() || response.lost_focus() {
+            edit = Some(format!("Change timing of shot from {shooter}"));
+        }
+
+        ui.label("aiming");
+        let mut target = shot.target;
+        egui::ComboBox::from_id_source("shot target")
+            .selected_text(target.name())
+            .show_ui(ui, |ui| {
+                for option in GoalTarget::ALL {
+                    ui.selectable_value(&mut target, option, option.name());
+                }
+            });
+        if target != shot.target {
+            shot.target = target;
+            edit = Some(format!("Change target of shot from {shooter}"));
+        }
+
+        ui.label("Goalkeeper goes");
+        let mut reaction = shot.reaction;
+        egui::ComboBox::from_id_source("shot reaction")
+            .selected_text(reaction.map_or("nowhere", |reaction| reaction.name()))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut reaction, None, "nowhere");
+                for option in GoalTarget::ALL {
+                    ui.selectable_value(&mut reaction, Some(option), option.name());
+                }
+            });
+        if reaction != shot.reaction {
+            shot.reaction = reaction;
+            edit = Some(format!("Change goalkeeper reaction to shot from {shooter}"));
+        }
+
+        if ui.button("🗑").on_hover_text("Delete the shot").clicked() {
+            frame.shot = None;
+            edit = Some(format!("Delete shot from {shooter}"));
+        }
+    });
+
+    edit
+}
+
+/// Shows the angle the shooter has at the goal, the line of the shot, and a
+/// view of the goal from the front with where the shot and the goalkeeper go
+fn draw_shot(ui: &mut Ui, to_screen: RectTransform, frame: &Step) {
+    let (Some(shot), Some((from, to, _, _))) = (&frame.shot, frame.shot_flight()) else {
+        return;
+    };
+    let ball_color = PlayerType::Ball.color();
+
+    let cone: Vec<Point> = [from]
+        .into_iter()
+        .chain(goal_posts(from))
+        .map(|pt| get_screen_coords(pt, to_screen))
+        .collect();
+    ui.painter().add(Shape::convex_polygon(
+        cone,
+        ball_color.gamma_multiply(0.15),
+        Stroke::new(1.0_f32, ball_color.gamma_multiply(0.5)),
+    ));
+    let from = get_screen_coords(from, to_screen);
+    let to = get_screen_coords(to, to_screen);
+    ui.painter()
+        .arrow(from, to - from, Stroke::new(2.0_f32, ball_color));
+
+    // The goal as the shooter sees it, in the corner of the field
+    let clip = ui.clip_rect();
+    let scale = 24.0;
+    let size = Vec2::new(GOAL_WIDTH, GOAL_HEIGHT) * scale;
+    let goal = Rect::from_min_size(clip.right_top() + Vec2::new(-size.x - 12.0, 12.0), size);
+    let in_goal = |target: GoalTarget| {
+        let x = goal.center().x + target.across() * scale;
+        let y = goal.bottom() - target.height() * scale;
+        Point::new(x, y)
+    };
+    ui.painter()
+        .rect_filled(goal.expand(4.0), 2.0, Color32::from_black_alpha(120));
+    ui.painter().add(Shape::line(
+        vec![
+            goal.left_bottom(),
+            goal.left_top(),
+            goal.right_top(),
+            goal.right_bottom(),
+        ],
+        Stroke::new(3.0_f32, Color32::RED),
+    ));
+    if let Some(reaction) = shot.reaction {
+        let keeper = PlayerType::DefendingGoalkeeper.color();
+        ui.painter()
+            .circle_stroke(in_goal(reaction), 0.4 * scale, Stroke::new(2.0_f32, keeper));
     }
+    ui.painter()
+        .circle_filled(in_goal(shot.target), 0.12 * scale, ball_color);
 }
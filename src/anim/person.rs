@@ -1,100 +1,59 @@
-use std::sync::atomic::{AtomicUsize, Ordering};
+//! Drawing actors, and letting the user move them and edit their paths.
 
 use egui::{
-    epaint::CubicBezierShape, Align2, Color32, FontId, Id, Pos2, Rect, Sense, Stroke, Ui, Vec2,
+    emath::RectTransform, epaint::CubicBezierShape, Align2, Color32, FontId, Id, Rect, Response,
+    Sense, Stroke, Ui, Vec2,
 };
 
-use super::util::{bez_at_t, get_screen_coords, screen_d_to_frac};
-
-pub type Point = Pos2;
-
-#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
-pub enum PlayerType {
-    Attacking,
-    Defending,
-    Ball,
-}
-
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub enum Movement {
-    None(Point),
-    Bezier([Point; 4]),
-}
+use crate::model::{
+    save::COORD_RANGE,
+    spline::{Spline, MAX_SEGMENTS},
+    Actor, Glyph, Path, Point,
+};
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct Person {
-    pub ids: [Id; 4],
-    pub movement: Movement,
-    pub label: String,
-    pub p_type: PlayerType,
+use super::util::{get_field_coords, get_screen_coords, screen_d_to_field};
+
+/// Drawing actors on the field, and letting the user edit them
+pub trait ActorUi {
+    /// Lets the user move and edit the person, with widgets identified by `id`.
+    /// Returns the response of the main dot, and a description of the edit
+    /// when one is finished.
+    fn display(
+        &mut self,
+        ui: &mut Ui,
+        to_screen: RectTransform,
+        id: Id,
+        selected: bool,
+    ) -> (Response, Option<String>);
+
+    /// Draws the person and its movement, without letting the user edit it
+    fn draw(&self, ui: &mut Ui, to_screen: RectTransform);
+
+    /// Draws the person at `pt`, which is usually somewhere along its path
+    fn animate(&self, ui: &mut Ui, to_screen: RectTransform, pt: Point);
+
+    /// Draws the person `height` meters above `pt`, larger and with a shadow on the floor.
+    /// Used for the ball.
+    fn animate_in_air(&self, ui: &mut Ui, to_screen: RectTransform, pt: Point, height: f32);
+
+    /// Writes `speed` below the person standing at `pt`
+    fn draw_speed(&self, ui: &mut Ui, to_screen: RectTransform, pt: Point, speed: f32);
 }
 
-impl Person {
-    pub fn new(movement: Movement, label: &str, p_type: PlayerType) -> Self {
-        static COUNTER: AtomicUsize = AtomicUsize::new(0);
-        let id = COUNTER.fetch_add(1, Ordering::Relaxed);
-        let ids = [
-            Id::new(format!("{id} {label} 1")),
-            Id::new(format!("{id} {label} 2")),
-            Id::new(format!("{id} {label} 3")),
-            Id::new(format!("{id} {label} 4")),
-        ];
-        Self {
-            ids,
-            movement,
-            label: label.to_string(),
-            p_type,
-        }
-    }
-
-    pub fn still(pt: Point, label: &str, p_type: PlayerType) -> Self {
-        let movement = Movement::None(pt);
-        Self::new(movement, label, p_type)
-    }
-
-    #[allow(dead_code)]
-    pub fn moving(pts: [Point; 4], label: &str, p_type: PlayerType) -> Self {
-        let movement = Movement::Bezier(pts);
-        Self::new(movement, label, p_type)
-    }
-
-    #[allow(dead_code)]
-    pub fn from_prev(prev: Self) -> Self {
-        match prev.movement {
-            Movement::Bezier(pts) => {
-                let prev_mvmnt = pts[3] - pts[0];
-                let prev_speed = pts[3] - pts[2];
-                let prev_last_speed = pts[3] - pts[1];
-                let pts = [
-                    pts[3],
-                    pts[3] + prev_speed,
-                    pts[3] + prev_last_speed,
-                    pts[3] + prev_mvmnt,
-                ];
-                Self::moving(pts, &prev.label, prev.p_type)
-            }
-            Movement::None(pt) => Self::still(pt, &prev.label, prev.p_type),
-        }
-    }
-
-    fn get_color(&self) -> Color32 {
-        match self.p_type {
-            PlayerType::Attacking => Color32::RED,
-            PlayerType::Defending => Color32::BLUE,
-            _ => Color32::YELLOW,
-        }
-    }
-
-    pub fn display(&mut self, ui: &mut Ui, rect: Rect) {
+impl ActorUi for Actor {
+    fn display(
+        &mut self,
+        ui: &mut Ui,
+        to_screen: RectTransform,
+        id: Id,
+        selected: bool,
+    ) -> (Response, Option<String>) {
         let radius: f32 = 10.0;
-        let screen_pt = match self.movement {
-            Movement::Bezier(pts) => get_screen_coords(pts[0], rect),
-            Movement::None(pt) => get_screen_coords(pt, rect),
-        };
+        let screen_pt = get_screen_coords(self.path.start(), to_screen);
 
         // Check for clicks
         let bounding_rect = Rect::from_center_size(screen_pt, Vec2::splat(radius * 1.7));
-        let i = ui.interact(bounding_rect, self.ids[0], Sense::click_and_drag());
+        let i = ui.interact(bounding_rect, id, Sense::click_and_drag());
 
         let draw_radius = if i.dragged() {
             ui.ctx().animate_value_with_time(i.id, 13.0, 0.1)
@@ -106,108 +65,276 @@ impl Person {
 
         if i.dragged() {
             let d = i.drag_delta();
-            match &mut self.movement {
-                Movement::Bezier(pts) => {
-                    for pt in pts.iter_mut() {
-                        *pt += screen_d_to_frac(d, rect);
-                    }
-                }
-                Movement::None(pt) => {
-                    *pt += screen_d_to_frac(d, rect);
+            self.path.translate(screen_d_to_field(d, to_screen));
+        }
+
+        let mut edit = i.drag_released().then(|| format!("Move {}", self.name()));
+
+        if self.unlinked {
+            i.context_menu(|ui| {
+                if ui.button("Link to previous step").clicked() {
+                    self.unlinked = false;
+                    edit = Some(format!("Link {} to previous step", self.name()));
+                    ui.close_menu();
                 }
-            }
+            });
         }
 
-        if i.double_clicked() {
-            let root = match &self.movement {
-                Movement::Bezier(pts) => pts[0],
-                Movement::None(pt) => *pt,
-            };
-            match self.movement {
-                Movement::Bezier(_) => self.movement = Movement::None(root),
-                Movement::None(_) => {
-                    self.movement = Movement::Bezier([
+        if i.double_clicked() && self.p_type.can_move() {
+            edit = Some(format!("Toggle movement of {}", self.name()));
+            let root = self.path.start();
+            match self.path {
+                Path::Spline(_) => self.path = Path::None(root),
+                Path::None(_) => {
+                    self.path = Path::Spline(Spline::new([
                         root,
-                        root + Vec2 { x: 0.05, y: 0.0 },
-                        root + Vec2 { x: 0.1, y: 0.05 },
-                        root + Vec2 { x: 0.1, y: 0.1 },
-                    ])
+                        root + Vec2 { x: 1.0, y: 0.0 },
+                        root + Vec2 { x: 2.0, y: 1.0 },
+                        root + Vec2 { x: 2.0, y: 2.0 },
+                    ]))
                 }
             }
         }
 
         // Edit ui if we have movement
-        if let Movement::Bezier(_) = self.movement {
-            self.draw_lines(ui, rect);
-            for dot_idx in 1..4 {
-                self.draw_dot(ui, rect, dot_idx);
+        if let Path::Spline(_) = self.path {
+            draw_lines(self, ui, to_screen);
+            if let Some(path_edit) = add_waypoint(self, ui, to_screen, id) {
+                edit = Some(path_edit);
+            }
+            let mut dot_idx = 1;
+            while let Path::Spline(spline) = &self.path {
+                if dot_idx >= spline.points.len() {
+                    break;
+                }
+                if let Some(dot_edit) = draw_dot(self, ui, to_screen, id, dot_idx) {
+                    edit = Some(dot_edit);
+                }
+                dot_idx += 1;
             }
         }
+        // Dragged far off the court, the play could not be opened again
+        self.path.clamp_to(&COORD_RANGE);
+
+        // Draw main dot
+        draw_marker(self, ui, screen_pt, draw_radius);
+        if selected {
+            let stroke = Stroke::new(2.0_f32, ui.visuals().strong_text_color());
+            ui.painter()
+                .circle_stroke(screen_pt, draw_radius + 3.0, stroke);
+        }
+
+        (i, edit)
+    }
+
+    fn draw(&self, ui: &mut Ui, to_screen: RectTransform) {
+        if let Path::Spline(spline) = &self.path {
+            draw_lines(self, ui, to_screen);
+            let col = self.get_color();
+            for pt in &spline.points[1..] {
+                ui.painter()
+                    .circle(get_screen_coords(*pt, to_screen), 5.0, col, Stroke::NONE);
+            }
+        }
+        self.animate(ui, to_screen, self.path.start());
+    }
 
-        let col = self.get_color();
+    fn animate(&self, ui: &mut Ui, to_screen: RectTransform, pt: Point) {
+        let radius: f32 = 10.0;
+
+        let screen_pt = get_screen_coords(pt, to_screen);
 
         // Draw main dot
-        ui.painter()
-            .circle(screen_pt, draw_radius, col, Stroke::NONE);
+        draw_marker(self, ui, screen_pt, radius);
+    }
+
+    fn animate_in_air(&self, ui: &mut Ui, to_screen: RectTransform, pt: Point, height: f32) {
+        let radius: f32 = 6.0;
+        let screen_pt = get_screen_coords(pt, to_screen);
+        let shadow = Color32::from_black_alpha(80);
+        ui.painter().circle_filled(screen_pt, radius, shadow);
+        let lifted = screen_pt - Vec2::new(0.0, height * 3.0);
+        draw_marker(self, ui, lifted, radius * (1.0 + height / 4.0));
+    }
+
+    fn draw_speed(&self, ui: &mut Ui, to_screen: RectTransform, pt: Point, speed: f32) {
+        let screen_pt = get_screen_coords(pt, to_screen);
         ui.painter().text(
-            screen_pt,
-            Align2::CENTER_CENTER,
-            &self.label,
-            FontId::default(),
-            Color32::WHITE,
+            screen_pt + Vec2::new(0.0, 12.0),
+            Align2::CENTER_TOP,
+            format_speed(speed),
+            FontId::proportional(11.0),
+            ui.visuals().text_color(),
         );
     }
+}
 
-    fn draw_dot(&mut self, ui: &mut Ui, rect: Rect, dot_idx: usize) {
-        let radius: f32 = 5.0;
-        let screen_pt = match self.movement {
-            Movement::Bezier(pts) => get_screen_coords(pts[dot_idx], rect),
-            Movement::None(_) => panic!("Dots should not be drawn if we dont have bezier"),
-        };
+/// Draws the glyph of the person with the jersey number, or else the label,
+/// on it. The player name is written above.
+fn draw_marker(actor: &Actor, ui: &Ui, screen_pt: Point, radius: f32) {
+    draw_glyph(
+        ui,
+        actor.p_type.glyph(),
+        screen_pt,
+        radius,
+        actor.get_color(),
+    );
+    let text = match actor.number {
+        Some(number) => number.to_string(),
+        None => actor.label.clone(),
+    };
+    ui.painter().text(
+        screen_pt,
+        Align2::CENTER_CENTER,
+        text,
+        FontId::default(),
+        Color32::WHITE,
+    );
+    if !actor.player_name.is_empty() {
+        ui.painter().text(
+            screen_pt - Vec2::new(0.0, radius + 2.0),
+            Align2::CENTER_BOTTOM,
+            &actor.player_name,
+            FontId::proportional(11.0),
+            ui.visuals().text_color(),
+        );
+    }
+}
 
-        // Check for clicks
-        let bounding_rect = Rect::from_center_size(screen_pt, Vec2::splat(radius * 1.7));
-        let i = ui.interact(bounding_rect, self.ids[dot_idx], Sense::click_and_drag());
+/// Lets the user add a waypoint by clicking on the path
+fn add_waypoint(
+    actor: &mut Actor,
+    ui: &mut Ui,
+    to_screen: RectTransform,
+    id: Id,
+) -> Option<String> {
+    let Path::Spline(spline) = &actor.path else {
+        return None;
+    };
+    let pointer = ui.ctx().pointer_hover_pos()?;
+    if !to_screen.to().contains(pointer) || spline.num_segments() >= MAX_SEGMENTS {
+        return None;
+    }
 
-        // Nice ui
-        let draw_radius = if i.dragged() {
-            ui.ctx().animate_value_with_time(i.id, 7.0, 0.1)
-        } else if i.hovered() {
-            ui.ctx().animate_value_with_time(i.id, 6.0, 0.1)
-        } else {
-            ui.ctx().animate_value_with_time(i.id, radius, 0.1)
-        };
+    let (segment, t, closest) = spline.closest(get_field_coords(pointer, to_screen));
+    let screen_pt = get_screen_coords(closest, to_screen);
+    // Leave clicks close to the dots to the dots
+    let near_dot = std::iter::once(actor.path.start())
+        .chain(actor.path.points().iter().copied())
+        .any(|pt| get_screen_coords(pt, to_screen).distance(pointer) < 10.0);
+    if near_dot || screen_pt.distance(pointer) > 6.0 {
+        return None;
+    }
 
-        // Move dot
-        if i.dragged() {
-            let d = i.drag_delta();
-            if let Movement::Bezier(pts) = &mut self.movement {
-                pts[dot_idx] += screen_d_to_frac(d, rect);
-                if dot_idx == 3 {
-                    pts[2] += screen_d_to_frac(d, rect);
-                }
+    let bounding_rect = Rect::from_center_size(screen_pt, Vec2::splat(12.0));
+    let i = ui
+        .interact(bounding_rect, id.with("path"), Sense::click())
+        .on_hover_text("Click to add a waypoint");
+    ui.painter()
+        .circle_stroke(screen_pt, 4.0, Stroke::new(1.5_f32, actor.get_color()));
+
+    if !i.clicked() {
+        return None;
+    }
+    if let Path::Spline(spline) = &mut actor.path {
+        spline.insert_knot(segment, t);
+    }
+    Some(format!("Add waypoint to {}", actor.name()))
+}
+
+fn draw_dot(
+    actor: &mut Actor,
+    ui: &mut Ui,
+    to_screen: RectTransform,
+    id: Id,
+    dot_idx: usize,
+) -> Option<String> {
+    let radius: f32 = 5.0;
+    let Path::Spline(spline) = &mut actor.path else {
+        panic!("Dots should not be drawn if we dont have a path");
+    };
+    let screen_pt = get_screen_coords(spline.points[dot_idx], to_screen);
+    let knot = Spline::is_knot(dot_idx).then_some(dot_idx / 3);
+
+    // Check for clicks
+    let bounding_rect = Rect::from_center_size(screen_pt, Vec2::splat(radius * 1.7));
+    let i = ui.interact(bounding_rect, id.with(dot_idx), Sense::click_and_drag());
+
+    // Nice ui
+    let draw_radius = if i.dragged() {
+        ui.ctx().animate_value_with_time(i.id, 7.0, 0.1)
+    } else if i.hovered() {
+        ui.ctx().animate_value_with_time(i.id, 6.0, 0.1)
+    } else {
+        ui.ctx().animate_value_with_time(i.id, radius, 0.1)
+    };
+
+    // Move dot
+    if i.dragged() {
+        spline.move_point(dot_idx, screen_d_to_field(i.drag_delta(), to_screen));
+    }
+
+    let mut edit = None;
+    if let Some(knot) = knot.filter(|&knot| spline.is_interior(knot)) {
+        let sharp = spline.is_sharp(knot);
+        i.context_menu(|ui| {
+            let toggle = if sharp {
+                "Make smooth"
+            } else {
+                "Make sharp corner"
+            };
+            if ui.button(toggle).clicked() {
+                spline.set_sharp(knot, !sharp);
+                edit = Some("Change waypoint");
+                ui.close_menu();
             }
+            if ui.button("Delete waypoint").clicked() {
+                spline.remove_knot(knot);
+                edit = Some("Delete waypoint");
+                ui.close_menu();
+            }
+        });
+    }
+
+    let col = actor.get_color();
+
+    // Draw
+    match knot {
+        Some(knot) if is_sharp_knot(actor, knot) => {
+            let corner = Rect::from_center_size(screen_pt, Vec2::splat(draw_radius * 1.8));
+            ui.painter().rect_filled(corner, 0.0, col);
+        }
+        _ => {
+            ui.painter()
+                .circle(screen_pt, draw_radius, col, Stroke::NONE);
         }
+    }
 
-        let col = self.get_color();
+    if let Some(edit) = edit {
+        return Some(format!("{edit} of {}", actor.name()));
+    }
+    i.drag_released()
+        .then(|| format!("Move path of {}", actor.name()))
+}
 
-        // Draw
-        ui.painter()
-            .circle(screen_pt, draw_radius, col, Stroke::NONE);
+fn is_sharp_knot(actor: &Actor, knot: usize) -> bool {
+    match &actor.path {
+        Path::Spline(spline) => spline.is_sharp(knot),
+        Path::None(_) => false,
     }
+}
 
-    fn draw_lines(&mut self, ui: &mut Ui, rect: Rect) {
-        let stroke = Stroke {
-            width: 1.0,
-            color: Color32::GRAY,
-        };
+fn draw_lines(actor: &Actor, ui: &mut Ui, to_screen: RectTransform) {
+    let stroke = Stroke {
+        width: 1.0,
+        color: Color32::GRAY,
+    };
 
-        let mut points = [Point::ZERO; 4];
-        if let Movement::Bezier(pts) = &mut self.movement {
-            for (screen_pt, frac_pt) in points.iter_mut().zip(pts.iter()) {
-                *screen_pt = get_screen_coords(*frac_pt, rect);
-            }
-        }
+    let Path::Spline(spline) = &actor.path else {
+        return;
+    };
+    for segment in spline.segments() {
+        let points = segment.map(|pt| get_screen_coords(pt, to_screen));
 
         let bez = CubicBezierShape {
             closed: false,
@@ -223,28 +350,55 @@ impl Person {
 
         ui.painter().add(bez);
     }
+}
 
-    pub fn animate(&self, ui: &mut Ui, rect: Rect, t: f32) {
-        let radius: f32 = 10.0;
-
-        let screen_pt = match self.movement {
-            Movement::Bezier(pts) => {
-                let pt = bez_at_t(pts, t);
-
-                get_screen_coords(pt, rect)
-            }
-            Movement::None(pt) => get_screen_coords(pt, rect),
-        };
+pub fn format_speed(speed: f32) -> String {
+    if speed.is_finite() {
+        format!("{speed:.1} m/s")
+    } else {
+        "instant".to_owned()
+    }
+}
 
-        let col = self.get_color();
-        // Draw main dot
-        ui.painter().circle(screen_pt, radius, col, Stroke::NONE);
-        ui.painter().text(
-            screen_pt,
-            Align2::CENTER_CENTER,
-            &self.label,
-            FontId::default(),
-            Color32::WHITE,
-        );
+/// Draws `glyph` around `center`, about as large as a circle of `radius`
+pub fn draw_glyph(ui: &Ui, glyph: Glyph, center: Point, radius: f32, color: Color32) {
+    let painter = ui.painter();
+    let outline = Stroke::new(1.5_f32, Color32::WHITE);
+    match glyph {
+        Glyph::Circle => {
+            painter.circle(center, radius, color, Stroke::NONE);
+        }
+        Glyph::Square => {
+            let rect = Rect::from_center_size(center, Vec2::splat(radius * 1.8));
+            painter.rect(rect, radius * 0.3, color, Stroke::NONE);
+        }
+        Glyph::Striped => {
+            painter.circle(center, radius, color, Stroke::NONE);
+            painter.circle_stroke(center, radius - 1.5, outline);
+        }
+        Glyph::Triangle => {
+            let pts = [-90.0_f32, 30.0, 150.0]
+                .map(|deg| center + radius * Vec2::angled(deg.to_radians()));
+            painter.add(egui::Shape::convex_polygon(
+                pts.to_vec(),
+                color,
+                Stroke::NONE,
+            ));
+        }
+        Glyph::Bar => {
+            let rect = Rect::from_center_size(center, Vec2::new(radius * 0.6, radius * 2.0));
+            painter.rect(rect, 1.0, color, Stroke::NONE);
+        }
+        Glyph::Mat => {
+            let rect = Rect::from_center_size(center, Vec2::new(radius * 3.0, radius * 1.6));
+            painter.rect(rect, 2.0, color, Stroke::NONE);
+        }
+        Glyph::Frame => {
+            let rect = Rect::from_center_size(center, Vec2::splat(radius * 1.8));
+            painter.rect(rect, 0.0, color, Stroke::NONE);
+            let net = Stroke::new(1.0_f32, Color32::WHITE);
+            painter.line_segment([rect.left_top(), rect.right_bottom()], net);
+            painter.line_segment([rect.right_top(), rect.left_bottom()], net);
+        }
     }
 }
@@ -1,64 +1,472 @@
-use egui::Id;
+use crate::export::{animated, pdf, svg, Ink};
+use crate::file_io::FileIo;
+use crate::history::History;
+use crate::library::{Library, PlayEntry};
+use crate::play_file;
+use crate::playback::Playback;
+use crate::share::SharedPlay;
+use crate::{
+    anim::{formation::Formations, Animation, EditorState},
+    model::{
+        save::{self, MAX_FRAMES},
+        Play,
+    },
+};
 
-use crate::anim::person::{Person, PlayerType, Point};
-use crate::anim::Animation;
+/// Storage key for the play library, which is saved as versioned json next to the app state.
+const LIBRARY_KEY: &str = "library";
+
+/// Storage key the saved library is copied to when it cannot be opened, so
+/// that saving the library that replaces it does not lose it.
+const LIBRARY_BACKUP_KEY: &str = "library backup";
+
+/// Storage key used by older versions, which only kept a single animation.
+const LEGACY_ANIMATION_KEY: &str = "animation";
 
 /// We derive Deserialize/Serialize so we can persist app state on shutdown.
-#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(Default, serde::Deserialize, serde::Serialize)]
 #[serde(default)] // if we add new fields, give them default values when deserializing old state
 pub struct JuggeApp {
-    #[serde(skip)] // opted out for debug reasons
-    animation: Animation,
+    #[serde(skip)] // persisted under `LIBRARY_KEY` so it can be migrated
+    library: Library,
 
-    #[serde(skip)] // opted out on purpose
-    is_animating: bool,
-}
+    /// The saved library, if it could not be fully opened, to back up on the next save
+    #[serde(skip)]
+    library_backup: Option<String>,
 
-impl Default for JuggeApp {
-    fn default() -> Self {
-        Self {
-            animation: Animation::new(vec![
-                Person::still(Point::new(0.02, 0.02), "LW", PlayerType::Attacking),
-                Person::still(Point::new(0.11, 0.56), "LB", PlayerType::Attacking),
-                Person::still(Point::new(0.50, 0.62), "CB", PlayerType::Attacking),
-                Person::still(Point::new(0.50, 0.32), "PV", PlayerType::Attacking),
-                Person::still(Point::new(0.89, 0.56), "RB", PlayerType::Attacking),
-                Person::still(Point::new(0.98, 0.02), "RW", PlayerType::Attacking),
-                // Defense
-                Person::still(Point::new(0.16, 0.15), "LW", PlayerType::Defending),
-                Person::still(Point::new(0.28, 0.28), "LB", PlayerType::Defending),
-                Person::still(Point::new(0.43, 0.32), "CB", PlayerType::Defending),
-                Person::still(Point::new(0.57, 0.32), "PV", PlayerType::Defending),
-                Person::still(Point::new(0.72, 0.28), "RB", PlayerType::Defending),
-                Person::still(Point::new(0.84, 0.15), "RW", PlayerType::Defending),
-                // Ball
-                Person::still(Point::new(0.50, 0.65), "", PlayerType::Ball),
-            ]),
-            is_animating: false,
-        }
-    }
+    #[serde(skip)]
+    playback: Playback,
+
+    #[serde(skip)]
+    history: History,
+
+    /// Where the user is in the current play of the library
+    #[serde(skip)]
+    editor: EditorState,
+
+    #[serde(skip)]
+    file_io: FileIo,
+
+    /// A play opened from a link, shown read only instead of the library
+    #[serde(skip)]
+    shared: Option<(Play, EditorState)>,
+
+    /// Error to show to the user
+    #[serde(skip)]
+    error: Option<String>,
+
+    /// Write the speed of every moving player on the field
+    show_speeds: bool,
+
+    /// Formations the user has saved
+    formations: Formations,
+
+    /// Last used settings for exporting animations
+    animation_export: animated::Settings,
+
+    #[serde(skip)]
+    show_animation_export: bool,
+
+    /// The animation being exported, a few frames every update
+    #[serde(skip)]
+    animation_job: Option<AnimationJob>,
+
+    /// Last used settings for printing playbooks
+    playbook: pdf::Settings,
+
+    /// Which plays of the library go in the playbook, while picking them
+    #[serde(skip)]
+    playbook_plays: Option<Vec<bool>>,
 }
 
 impl JuggeApp {
     /// Called once before the first frame.
-    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>, shared: Option<SharedPlay>) -> Self {
+        let mut app = Self::load(cc);
+        app.shared = shared.map(|shared| (shared.play, EditorState::default()));
+        app.opened_play();
+        app
+    }
+
+    /// Called when another play is opened in the editor
+    fn opened_play(&mut self) {
+        self.playback.stop();
+        self.editor = EditorState::default();
+        self.history
+            .reset(&self.library.current().play, self.editor);
+    }
+
+    fn record_edit(&mut self, label: String) {
+        let play = self.library.current_mut();
+        if self.history.record(label, &play.play, self.editor) {
+            play.touch();
+        }
+    }
+
+    fn restore(&mut self, (play, editor): (Play, EditorState)) {
+        let entry = self.library.current_mut();
+        entry.play = play;
+        entry.touch();
+        self.editor = editor;
+        self.playback.stop();
+    }
+
+    fn undo_redo_shortcuts(&mut self, ctx: &egui::Context) {
+        use egui::{Key, KeyboardShortcut, Modifiers};
+
+        const UNDO: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+        const REDO: KeyboardShortcut =
+            KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::Z);
+
+        // Text fields have their own undo
+        if self.shared.is_some() || ctx.wants_keyboard_input() {
+            return;
+        }
+        // Redo first, since undo also matches when shift is held
+        let restore = if ctx.input_mut(|i| i.consume_shortcut(&REDO)) {
+            self.history.redo()
+        } else if ctx.input_mut(|i| i.consume_shortcut(&UNDO)) {
+            self.history.undo()
+        } else {
+            None
+        };
+        if let Some(restore) = restore {
+            self.restore(restore);
+        }
+    }
+
+    fn load(cc: &eframe::CreationContext<'_>) -> Self {
         // This is also where you can customize the look and feel of egui using
         // `cc.egui_ctx.set_visuals` and `cc.egui_ctx.set_fonts`.
 
         // Load previous app state (if any).
         // Note that you must enable the `persistence` feature for this to work.
         if let Some(storage) = cc.storage {
-            return eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
+            let mut app: Self = eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
+            if let Some(json) = storage.get_string(LIBRARY_KEY) {
+                match Library::from_json(&json) {
+                    Ok((library, skipped)) => {
+                        app.library = library;
+                        if !skipped.is_empty() {
+                            app.error = Some(format!(
+                                "Some saved plays could not be opened, and were left out of the library. \
+                                 The saved library is backed up.\n\n{}",
+                                skipped.join("\n")
+                            ));
+                            app.library_backup = Some(json);
+                        }
+                    }
+                    Err(err) => {
+                        log::warn!("Failed to load saved library: {err}");
+                        app.error = Some(format!(
+                            "The saved library could not be opened, so a new one was started. \
+                             The saved library is backed up.\n\n{err}"
+                        ));
+                        app.library_backup = Some(json);
+                    }
+                }
+            } else if let Some(json) = storage.get_string(LEGACY_ANIMATION_KEY) {
+                match save::from_json(&json) {
+                    Ok(play) => app.library = Library::from_play(play),
+                    Err(err) => {
+                        log::warn!("Failed to load saved animation: {err}");
+                        app.error = Some(format!("The saved play could not be opened: {err}"));
+                    }
+                }
+            }
+            return app;
         }
 
         Default::default()
     }
+
+    fn open_file(&mut self) {
+        let Some(opened) = self.file_io.poll_opened() else {
+            return;
+        };
+        let result = opened.and_then(|file| {
+            let json = String::from_utf8(file.contents)
+                .map_err(|_| format!("{} is not a text file", file.name))?;
+            play_file::from_json(&json)
+                .map_err(|err| format!("Could not open {}: {err}", file.name))
+        });
+        match result {
+            Ok(play) => {
+                self.library.add(play);
+                self.opened_play();
+            }
+            Err(err) => self.error = Some(err),
+        }
+    }
+
+    fn save_file(&mut self) {
+        let play = self.library.current();
+        let json = play_file::to_json(play);
+        let file_name = play_file::file_name(play);
+        if let Err(err) = self
+            .file_io
+            .save(&file_name, "application/json", json.as_bytes())
+        {
+            self.error = Some(err);
+        }
+    }
+
+    /// Saves a picture made by `export` from the current play
+    fn export(
+        &mut self,
+        extension: &str,
+        mime: &str,
+        export: impl Fn(&Play) -> Result<Vec<u8>, String>,
+    ) {
+        let play = self.library.current();
+        let file_name = play_file::file_name_with(play, extension);
+        let result =
+            export(&play.play).and_then(|contents| self.file_io.save(&file_name, mime, &contents));
+        if let Err(err) = result {
+            self.error = Some(err);
+        }
+    }
+
+    /// Asks how to export the animation, and exports it
+    fn animation_export_window(&mut self, ctx: &egui::Context) {
+        if !self.show_animation_export {
+            return;
+        }
+        let settings = &mut self.animation_export;
+        let job = &self.animation_job;
+        let mut export = false;
+        let mut close = false;
+        egui::Window::new("Export animation")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.add_enabled_ui(job.is_none(), |ui| {
+                    egui::Grid::new("animation export")
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            ui.label("Format");
+                            egui::ComboBox::from_id_source("animation format")
+                                .selected_text(settings.format.name())
+                                .show_ui(ui, |ui| {
+                                    for format in animated::Format::ALL {
+                                        ui.selectable_value(
+                                            &mut settings.format,
+                                            format,
+                                            format.name(),
+                                        );
+                                    }
+                                });
+                            ui.end_row();
+
+                            ui.label("Width");
+                            ui.add(
+                                egui::DragValue::new(&mut settings.width)
+                                    .clamp_range(animated::WIDTH_RANGE)
+                                    .suffix(" px"),
+                            );
+                            ui.end_row();
+
+                            ui.label("Frame rate");
+                            ui.add(
+                                egui::DragValue::new(&mut settings.fps)
+                                    .clamp_range(animated::FPS_RANGE)
+                                    .suffix(" fps"),
+                            );
+                            ui.end_row();
+                        });
+                });
+                if let Some(job) = job {
+                    ui.add(egui::ProgressBar::new(job.job.progress()).show_percentage());
+                }
+                ui.horizontal(|ui| {
+                    export = ui
+                        .add_enabled(job.is_none(), egui::Button::new("Export"))
+                        .clicked();
+                    close = ui.button("Cancel").clicked();
+                });
+            });
+
+        if export {
+            let settings = self.animation_export;
+            let play = self.library.current();
+            self.animation_job = Some(AnimationJob {
+                job: animated::Job::new(&play.play, settings),
+                file_name: play_file::file_name_with(play, settings.format.extension()),
+                mime: settings.format.mime(),
+            });
+        }
+        if close {
+            self.animation_job = None;
+            self.show_animation_export = false;
+        }
+        self.continue_animation_export(ctx);
+    }
+
+    /// Makes the next frame of the animation being exported, and saves it
+    /// when it is done
+    fn continue_animation_export(&mut self, ctx: &egui::Context) {
+        let Some(job) = &mut self.animation_job else {
+            return;
+        };
+        let result = match job.job.step() {
+            Ok(None) => {
+                ctx.request_repaint();
+                return;
+            }
+            Ok(Some(contents)) => self.file_io.save(&job.file_name, job.mime, &contents),
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            self.error = Some(err);
+        }
+        self.animation_job = None;
+        self.show_animation_export = false;
+    }
+
+    /// Asks which plays to print and how, and exports them as a PDF
+    fn playbook_window(&mut self, ctx: &egui::Context) {
+        let Some(picked) = &mut self.playbook_plays else {
+            return;
+        };
+        // The library may have changed since the window opened
+        picked.resize(self.library.plays.len(), false);
+        let settings = &mut self.playbook;
+        let mut export = false;
+        let mut close = false;
+        egui::Window::new("Export playbook")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("Plays");
+                egui::ScrollArea::vertical()
+                    .max_height(240.0)
+                    .show(ui, |ui| {
+                        for (play, picked) in self.library.plays.iter().zip(picked.iter_mut()) {
+                            ui.checkbox(picked, &play.name);
+                        }
+                    });
+                ui.horizontal(|ui| {
+                    if ui.small_button("All").clicked() {
+                        picked.fill(true);
+                    }
+                    if ui.small_button("None").clicked() {
+                        picked.fill(false);
+                    }
+                });
+                ui.separator();
+
+                egui::Grid::new("playbook export")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Page size");
+                        egui::ComboBox::from_id_source("playbook page size")
+                            .selected_text(settings.page_size.name())
+                            .show_ui(ui, |ui| {
+                                for page_size in pdf::PageSize::ALL {
+                                    ui.selectable_value(
+                                        &mut settings.page_size,
+                                        page_size,
+                                        page_size.name(),
+                                    );
+                                }
+                            });
+                        ui.end_row();
+
+                        ui.label("Colors");
+                        egui::ComboBox::from_id_source("playbook ink")
+                            .selected_text(settings.ink.name())
+                            .show_ui(ui, |ui| {
+                                for ink in Ink::ALL {
+                                    ui.selectable_value(&mut settings.ink, ink, ink.name());
+                                }
+                            });
+                        ui.end_row();
+
+                        ui.label("Coach notes");
+                        ui.checkbox(&mut settings.notes, "Print under each step");
+                        ui.end_row();
+                    });
+                ui.horizontal(|ui| {
+                    let any = picked.contains(&true);
+                    export = ui.add_enabled(any, egui::Button::new("Export")).clicked();
+                    close = ui.button("Cancel").clicked();
+                });
+            });
+
+        if export {
+            let plays: Vec<&PlayEntry> = self
+                .library
+                .plays
+                .iter()
+                .zip(picked.iter())
+                .filter(|(_, &picked)| picked)
+                .map(|(play, _)| play)
+                .collect();
+            let file_name = match plays[..] {
+                [play] => play_file::file_name_with(play, pdf::EXTENSION),
+                _ => format!("playbook.{}", pdf::EXTENSION),
+            };
+            let result = pdf::playbook(&plays, self.playbook)
+                .and_then(|contents| self.file_io.save(&file_name, pdf::MIME, &contents));
+            if let Err(err) = result {
+                self.error = Some(err);
+            }
+        }
+        if export || close {
+            self.playbook_plays = None;
+        }
+    }
+
+    fn shared_banner(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("You are viewing a shared play.");
+            if ui.button("Fork into my library").clicked() {
+                if let Some(play) = self.close_shared() {
+                    self.library.add(PlayEntry::new("Shared play", "", play));
+                    self.opened_play();
+                }
+            }
+            if ui.button("Close").clicked() {
+                self.close_shared();
+            }
+        });
+        ui.separator();
+    }
+
+    fn close_shared(&mut self) -> Option<Play> {
+        self.playback.stop();
+        #[cfg(target_arch = "wasm32")]
+        crate::share::clear_hash();
+        self.shared.take().map(|(play, _)| play)
+    }
+
+    fn show_error(&mut self, ctx: &egui::Context) {
+        let Some(error) = &self.error else {
+            return;
+        };
+        let mut close = false;
+        egui::Window::new("Something went wrong")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(error);
+                close = ui.button("OK").clicked();
+            });
+        if close {
+            self.error = None;
+        }
+    }
 }
 
 impl eframe::App for JuggeApp {
     /// Called by the frame work to save state before shutdown.
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
         eframe::set_value(storage, eframe::APP_KEY, self);
+        if let Some(backup) = self.library_backup.take() {
+            storage.set_string(LIBRARY_BACKUP_KEY, backup);
+        }
+        storage.set_string(LIBRARY_KEY, self.library.to_json());
     }
 
     /// Called each time the UI needs repainting, which may be many times per second.
@@ -66,74 +474,154 @@ impl eframe::App for JuggeApp {
         // Put your widgets into a `SidePanel`, `TopPanel`, `CentralPanel`, `Window` or `Area`.
         // For inspiration and more examples, go to https://emilk.github.io/egui
 
+        self.open_file();
+        self.show_error(ctx);
+        self.animation_export_window(ctx);
+        self.playbook_window(ctx);
+        self.undo_redo_shortcuts(ctx);
+
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             // The top panel is often a good place for a menu bar:
 
             egui::menu::bar(ui, |ui| {
-                // NOTE: no File->Quit on web pages!
-                let is_web = cfg!(target_arch = "wasm32");
-                if !is_web {
-                    ui.menu_button("File", |ui| {
+                ui.menu_button("File", |ui| {
+                    if ui.button("Open play…").clicked() {
+                        ui.close_menu();
+                        self.file_io
+                            .open(ctx, "Jugge play", &[play_file::EXTENSION]);
+                    }
+                    if ui.button("Save play…").clicked() {
+                        ui.close_menu();
+                        self.save_file();
+                    }
+                    ui.separator();
+                    if ui.button("Export step as SVG…").clicked() {
+                        ui.close_menu();
+                        let step = self.editor.cur_frame;
+                        self.export(svg::EXTENSION, svg::MIME, |play| {
+                            Ok(svg::step(play, step).into_bytes())
+                        });
+                    }
+                    if ui.button("Export storyboard as SVG…").clicked() {
+                        ui.close_menu();
+                        self.export(svg::EXTENSION, svg::MIME, |play| {
+                            Ok(svg::storyboard(play).into_bytes())
+                        });
+                    }
+                    if ui.button("Export animation…").clicked() {
+                        ui.close_menu();
+                        self.show_animation_export = true;
+                    }
+                    if ui.button("Export playbook as PDF…").clicked() {
+                        ui.close_menu();
+                        let current = self.library.current;
+                        let plays = (0..self.library.plays.len()).map(|idx| idx == current);
+                        self.playbook_plays = Some(plays.collect());
+                    }
+
+                    // NOTE: no File->Quit on web pages!
+                    let is_web = cfg!(target_arch = "wasm32");
+                    if !is_web {
+                        ui.separator();
                         if ui.button("Quit").clicked() {
                             ctx.send_viewport_cmd(egui::ViewportCommand::Close);
                         }
-                    });
-                    ui.add_space(16.0);
-                }
+                    }
+                });
+                ui.add_space(16.0);
 
                 egui::widgets::global_dark_light_mode_buttons(ui);
             });
         });
 
+        egui::SidePanel::left("library_panel").show(ctx, |ui| {
+            if self.library.display(ui) {
+                self.opened_play();
+            }
+        });
+
+        if self.shared.is_none() {
+            egui::SidePanel::right("history_panel").show(ctx, |ui| {
+                if let Some(restore) = self.history.display(ui) {
+                    self.restore(restore);
+                }
+            });
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
             // The central panel the region left after adding TopPanel's and SidePanel's
-            ui.heading("Handball move editor");
+            if self.shared.is_some() {
+                self.shared_banner(ui);
+            }
+
+            let editable = self.shared.is_none();
+            let (play, editor) = match &mut self.shared {
+                Some((play, editor)) => (play, editor),
+                None => {
+                    ui.heading(&self.library.current().name);
+                    (&mut self.library.current_mut().play, &mut self.editor)
+                }
+            };
+            let animation = &mut Animation { play, editor };
 
-            let numsteps = self.animation.frames.len() as f32;
-            let mut animation_time = ui.ctx().animate_value_with_time(
-                Id::new("main animation"),
-                numsteps - f32::EPSILON,
-                numsteps,
-            );
+            let duration = animation.play.duration();
+            self.playback.advance(ui.ctx(), duration);
 
+            let mut view_edit = None;
             ui.horizontal(|ui| {
-                if ui.button("Animate").clicked() {
-                    // Move time to start
-                    self.is_animating = true;
-                    animation_time =
-                        ui.ctx()
-                            .animate_value_with_time(Id::new("main animation"), 0.0, 0.0);
+                self.playback
+                    .display(ui, duration, &animation.play.step_starts());
+                ui.toggle_value(&mut self.show_speeds, "Speeds")
+                    .on_hover_text("Show how fast the players move");
+                if editable {
+                    view_edit = animation.display_view(ui);
+                    if let Some(formation_edit) = self.formations.display(ui, animation) {
+                        view_edit = Some(formation_edit);
+                    }
                 }
-                if ui.button("Reset").clicked() {
-                    // Move time to end
-                    self.is_animating = false;
+
+                #[cfg(target_arch = "wasm32")]
+                if ui
+                    .button("Copy link")
+                    .on_hover_text("Copy a link that opens this play")
+                    .clicked()
+                {
+                    if let Some(link) = crate::share::link(animation.play) {
+                        ui.ctx().copy_text(link);
+                    }
                 }
             });
 
             ui.separator();
 
-            let num_steps = self.animation.frames.len();
-            ui.horizontal(|ui| {
-                for i in 1..=num_steps {
-                    let step_name = i.to_string();
-                    if ui.button(step_name).clicked() {
-                        self.animation.cur_frame = i - 1;
-                        self.is_animating = false;
-                    }
+            let mut edit = step_bar(ui, animation, editable, &mut self.playback).or(view_edit);
+            if editable {
+                if let Some(players_edit) = animation.display_players(ui) {
+                    edit = Some(players_edit);
                 }
-                if ui.button("Add step").clicked() {
-                    self.animation.add_frame();
-                    self.is_animating = false;
+                if let Some(timing_edit) = animation.display_timing(ui) {
+                    edit = Some(timing_edit);
                 }
-            });
+                if let Some(passes_edit) = animation.display_passes(ui) {
+                    edit = Some(passes_edit);
+                }
+                if let Some(notes_edit) = animation.display_notes(ui) {
+                    edit = Some(notes_edit);
+                }
+            }
 
             ui.separator();
 
             // Display animation or display editing
-            if self.is_animating {
-                self.animation.display(ui, Some(animation_time));
-            } else {
-                self.animation.display(ui, None);
+            let show_speeds = self.show_speeds;
+            if self.playback.active {
+                animation.display(ui, Some(self.playback.time), editable, show_speeds);
+            } else if let Some(display_edit) = animation.display(ui, None, editable, show_speeds) {
+                edit = Some(display_edit);
+            }
+
+            if let Some(label) = edit {
+                self.record_edit(label);
             }
 
             ui.add(egui::github_link_file!(
@@ -144,20 +632,126 @@ impl eframe::App for JuggeApp {
     }
 }
 
-// Default positions
-
-// Attacking
-// LW: 0.02 0.02
-// LB: 0.11 0.56
-// CB: 0.5 0.62
-// PV: 0.5 0.32
-// RB: 0.89 0.56
-// RW: 0.98 0.02
-
-// Defending
-// LW: 0.16 0.15
-// LB: 0.28 0.28
-// CB: 0.43 0.32
-// PV: 0.57 0.32
-// RB: 0.72 0.28
-// RW: 0.16 0.15
+/// An animation being exported, and where it is saved when it is done
+struct AnimationJob {
+    job: animated::Job,
+    file_name: String,
+    mime: &'static str,
+}
+
+/// Payload when dragging a step to reorder it
+struct StepDrag(usize);
+
+/// Buttons for selecting, adding and reordering steps.
+/// Returns a description of the edit if the steps were changed.
+fn step_bar(
+    ui: &mut egui::Ui,
+    animation: &mut Animation<'_>,
+    editable: bool,
+    playback: &mut Playback,
+) -> Option<String> {
+    let mut edit = None;
+    let num_steps = animation.play.steps.len();
+    let can_add = num_steps < MAX_FRAMES;
+
+    ui.horizontal(|ui| {
+        for i in 0..num_steps {
+            let step_name = (i + 1).to_string();
+            let button = egui::Button::new(step_name).selected(i == animation.editor.cur_frame);
+            if !editable {
+                if ui.add(button).clicked() {
+                    animation.editor.cur_frame = i;
+                    playback.stop();
+                }
+                continue;
+            }
+
+            let response = ui
+                .add(button.sense(egui::Sense::click_and_drag()))
+                .on_hover_text("Drag to reorder, right click for more");
+            if response.clicked() {
+                animation.editor.cur_frame = i;
+                playback.stop();
+            }
+
+            // Reorder by dragging
+            response.dnd_set_drag_payload(StepDrag(i));
+            if let Some(drag) = response.dnd_hover_payload::<StepDrag>() {
+                let x = if drag.0 < i {
+                    response.rect.right()
+                } else {
+                    response.rect.left()
+                };
+                let stroke = ui.visuals().selection.stroke;
+                ui.painter().vline(x, response.rect.y_range(), stroke);
+            }
+            if let Some(drag) = response.dnd_release_payload::<StepDrag>() {
+                if drag.0 != i {
+                    animation.move_frame(drag.0, i);
+                    edit = Some(format!("Move step {} to {}", drag.0 + 1, i + 1));
+                    playback.stop();
+                }
+            }
+
+            response.context_menu(|ui| {
+                if ui
+                    .add_enabled(can_add, egui::Button::new("Insert step after"))
+                    .clicked()
+                {
+                    animation.insert_frame(i + 1);
+                    edit = Some(format!("Insert step {}", i + 2));
+                    playback.stop();
+                    ui.close_menu();
+                }
+                if ui
+                    .add_enabled(can_add, egui::Button::new("Duplicate step"))
+                    .clicked()
+                {
+                    animation.duplicate_frame(i);
+                    edit = Some(format!("Duplicate step {}", i + 1));
+                    playback.stop();
+                    ui.close_menu();
+                }
+                if ui
+                    .add_enabled(num_steps > 1, egui::Button::new("Delete step"))
+                    .clicked()
+                {
+                    animation.delete_frame(i);
+                    edit = Some(format!("Delete step {}", i + 1));
+                    playback.stop();
+                    ui.close_menu();
+                }
+            });
+        }
+
+        if editable
+            && ui
+                .add_enabled(can_add, egui::Button::new("Add step"))
+                .on_disabled_hover_text(format!("A play can have at most {MAX_FRAMES} steps"))
+                .clicked()
+        {
+            animation.add_frame();
+            edit = Some("Add step".to_owned());
+            playback.stop();
+        }
+
+        if editable {
+            ui.separator();
+            let response = ui
+                .checkbox(&mut animation.play.linked, "Linked steps")
+                .on_hover_text(
+                    "Every step starts where the previous one ended, also when you edit a step",
+                );
+            if response.changed() {
+                if animation.play.linked {
+                    animation.play.relink(0);
+                    edit = Some("Link steps".to_owned());
+                } else {
+                    edit = Some("Unlink steps".to_owned());
+                }
+            }
+        }
+    });
+
+    edit
+}
@@ -0,0 +1,285 @@
+#![warn(clippy::all, rust_2018_idioms)]
+
+//! Checks and converts plays from scripts, without opening a window.
+
+use std::{collections::HashMap, fmt::Write as _, process::ExitCode};
+
+use jugge::{
+    export::svg,
+    library::{Library, PlayEntry},
+    model::Play,
+    play_file,
+};
+
+const USAGE: &str = "\
+Usage:
+  jugge-cli validate <PLAY>...
+      Checks that the plays can be opened.
+  jugge-cli summary <PLAY>
+      Prints the players and steps of the play, and how far everyone moves.
+  jugge-cli convert <PLAY> <OUTPUT> [--step <N>] [--fps <N>]
+      Converts the play to the format of the extension of OUTPUT:
+        svg   a storyboard of every step, or only step N
+        json  the play file, migrated to the current version
+        csv   where everyone is, sampled N times per second (10 by default)
+  jugge-cli merge-libraries <OUTPUT> <LIBRARY>...
+      Merges libraries, or single plays, into one library. A play found
+      in several is kept once, as it was last changed.
+  jugge-cli help
+      Prints this.";
+
+const DEFAULT_FPS: u32 = 10;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some((command, args)) = args.split_first() else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    let result = match command.as_str() {
+        "validate" => validate(args),
+        "summary" => summary(args),
+        "convert" => convert(args),
+        "merge-libraries" => merge_libraries(args),
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => Err(format!("Unknown command \"{command}\"\n\n{USAGE}")),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn read(path: &str) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|err| format!("Could not read {path}: {err}"))
+}
+
+fn write(path: &str, contents: &[u8]) -> Result<(), String> {
+    std::fs::write(path, contents).map_err(|err| format!("Could not write {path}: {err}"))
+}
+
+fn open_play(path: &str) -> Result<PlayEntry, String> {
+    play_file::from_json(&read(path)?).map_err(|err| format!("{path}: {err}"))
+}
+
+/// Splits `--name value` options from the other arguments
+fn options<'a>(
+    args: &'a [String],
+    names: &[&str],
+) -> Result<(Vec<&'a str>, HashMap<String, &'a str>), String> {
+    let mut positional = Vec::new();
+    let mut options = HashMap::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.strip_prefix("--") {
+            Some(name) if names.contains(&name) => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("--{name} needs a value"))?;
+                options.insert(name.to_owned(), value.as_str());
+            }
+            Some(name) => return Err(format!("Unknown option --{name}")),
+            None => positional.push(arg.as_str()),
+        }
+    }
+    Ok((positional, options))
+}
+
+fn number(options: &HashMap<String, &str>, name: &str) -> Result<Option<u32>, String> {
+    options
+        .get(name)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| format!("--{name} must be a whole number, not \"{value}\""))
+        })
+        .transpose()
+}
+
+/// Prints whether each play is valid. Fails if any is not.
+fn validate(args: &[String]) -> Result<(), String> {
+    if args.is_empty() {
+        return Err(format!("Which plays should be validated?\n\n{USAGE}"));
+    }
+    let mut num_invalid = 0;
+    for path in args {
+        match open_play(path) {
+            Ok(_) => println!("{path}: ok"),
+            Err(err) => {
+                println!("{err}");
+                num_invalid += 1;
+            }
+        }
+    }
+    match num_invalid {
+        0 => Ok(()),
+        1 => Err("1 play is invalid".to_owned()),
+        _ => Err(format!("{num_invalid} plays are invalid")),
+    }
+}
+
+fn summary(args: &[String]) -> Result<(), String> {
+    let [path] = args else {
+        return Err(format!("Which play should be summarized?\n\n{USAGE}"));
+    };
+    let entry = open_play(path)?;
+    let play = &entry.play;
+
+    println!("{}", entry.name);
+    if !entry.author.is_empty() {
+        println!("By {}", entry.author);
+    }
+    if !entry.tags.is_empty() {
+        println!("Tags: {}", entry.tags.join(", "));
+    }
+    println!("{} steps, {:.1} s", play.steps.len(), play.duration());
+    for (idx, step) in play.steps.iter().enumerate() {
+        println!("  Step {}: {:.1} s", idx + 1, step.duration);
+    }
+
+    println!();
+    println!("{:<20} {:<22} {:>10}", "Player", "Type", "Distance");
+    for (idx, actor) in play.steps[0].actors.iter().enumerate() {
+        if !actor.p_type.can_move() {
+            continue;
+        }
+        let distance: f32 = play
+            .steps
+            .iter()
+            .map(|step| step.actors[idx].path.length())
+            .sum();
+        println!(
+            "{:<20} {:<22} {:>8.1} m",
+            actor.name(),
+            actor.p_type.name(),
+            distance
+        );
+    }
+    Ok(())
+}
+
+fn convert(args: &[String]) -> Result<(), String> {
+    let (paths, options) = options(args, &["step", "fps"])?;
+    let [path, output] = paths[..] else {
+        return Err(format!(
+            "Which play should be converted, and to what?\n\n{USAGE}"
+        ));
+    };
+    let entry = open_play(path)?;
+    let play = &entry.play;
+    let extension = std::path::Path::new(output)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    let contents = match extension.as_str() {
+        svg::EXTENSION => match number(&options, "step")? {
+            Some(step) => {
+                let num_steps = play.steps.len();
+                if !(1..=num_steps).contains(&(step as usize)) {
+                    return Err(format!("The play has steps 1 to {num_steps}, not {step}"));
+                }
+                svg::step(play, step as usize - 1)
+            }
+            None => svg::storyboard(play),
+        },
+        "json" | play_file::EXTENSION => play_file::to_json(&entry),
+        "csv" => {
+            let fps = number(&options, "fps")?.unwrap_or(DEFAULT_FPS);
+            if fps == 0 {
+                return Err("--fps must be at least 1".to_owned());
+            }
+            to_csv(play, fps)
+        }
+        _ => {
+            return Err(format!(
+                "Cannot convert to \"{output}\", use an .svg, .json or .csv file"
+            ))
+        }
+    };
+    write(output, contents.as_bytes())
+}
+
+/// Where everyone is, `fps` times per second
+fn to_csv(play: &Play, fps: u32) -> String {
+    let mut csv = "time,step,player,label,type,x,y\n".to_owned();
+    let num_samples = (play.duration() * fps as f32).round() as u32;
+    for sample in 0..=num_samples {
+        let time = sample as f32 / fps as f32;
+        let (step_idx, _) = play.step_at(time);
+        let actors = &play.steps[step_idx].actors;
+        for (idx, pt) in play.positions_at(time).into_iter().enumerate() {
+            let actor = &actors[idx];
+            // Writing to a string cannot fail
+            let _ = writeln!(
+                csv,
+                "{time:.3},{},{idx},{},{},{:.3},{:.3}",
+                step_idx + 1,
+                csv_field(&actor.label),
+                actor.p_type.name(),
+                pt.x,
+                pt.y
+            );
+        }
+    }
+    csv
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_owned()
+    }
+}
+
+fn merge_libraries(args: &[String]) -> Result<(), String> {
+    let [output, inputs @ ..] = args else {
+        return Err(format!("Where should the merged library go?\n\n{USAGE}"));
+    };
+    if inputs.is_empty() {
+        return Err(format!("Which libraries should be merged?\n\n{USAGE}"));
+    }
+
+    let mut plays: Vec<PlayEntry> = Vec::new();
+    for path in inputs {
+        let json = read(path)?;
+        let opened = match Library::from_json(&json) {
+            Ok((library, skipped)) => {
+                for err in skipped {
+                    eprintln!("{path}: left out {err}");
+                }
+                library.plays
+            }
+            // Not a library, so maybe a single play
+            Err(library_err) => vec![play_file::from_json(&json)
+                .map_err(|_| format!("{path} is neither a library nor a play: {library_err}"))?],
+        };
+        for play in opened {
+            // The same play, copied into several libraries
+            let same = plays
+                .iter_mut()
+                .find(|other| other.name == play.name && other.created == play.created);
+            match same {
+                Some(other) if other.modified < play.modified => *other = play,
+                Some(_) => {}
+                None => plays.push(play),
+            }
+        }
+    }
+
+    let num_plays = plays.len();
+    let mut library = Library::from_play(Play::default());
+    library.plays = plays;
+    library.current = 0;
+    write(output, library.to_json().as_bytes())?;
+    println!("Merged {num_plays} plays into {output}");
+    Ok(())
+}
@@ -0,0 +1,237 @@
+//! Animated GIF and APNG videos of plays, for sharing where the app cannot run.
+
+use std::{cell::RefCell, io::Write, rc::Rc};
+
+use super::{raster, Scene};
+use crate::model::Play;
+
+pub const WIDTH_RANGE: std::ops::RangeInclusive<u32> = 160..=1920;
+pub const FPS_RANGE: std::ops::RangeInclusive<u32> = 5..=50;
+/// How hard GIF colors are fitted, from 1 (best) to 30 (fastest)
+const GIF_QUANTIZE_SPEED: i32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Format {
+    Gif,
+    Apng,
+}
+
+impl Format {
+    pub const ALL: [Format; 2] = [Format::Gif, Format::Apng];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Gif => "GIF",
+            Format::Apng => "APNG",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Gif => "gif",
+            Format::Apng => "png",
+        }
+    }
+
+    pub fn mime(&self) -> &'static str {
+        match self {
+            Format::Gif => "image/gif",
+            Format::Apng => "image/apng",
+        }
+    }
+}
+
+/// How the animation is exported
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Settings {
+    pub format: Format,
+    /// In pixels. The height follows from the field.
+    pub width: u32,
+    /// Frames per second
+    pub fps: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            format: Format::Gif,
+            width: 480,
+            fps: 20,
+        }
+    }
+}
+
+/// The whole play, played once and then repeated
+pub fn export(play: &Play, settings: Settings) -> Result<Vec<u8>, String> {
+    let mut job = Job::new(play, settings);
+    loop {
+        if let Some(bytes) = job.step()? {
+            return Ok(bytes);
+        }
+    }
+}
+
+/// An export in progress. It is made one frame at a time, so that the app
+/// can show how far it has come and stay responsive meanwhile.
+pub struct Job {
+    play: Play,
+    format: Format,
+    width: u32,
+    fps: u32,
+    num_frames: u32,
+    /// Frames encoded so far
+    done: u32,
+    /// Started when the size of the first frame is known
+    encoder: Option<Encoder>,
+    output: Output,
+}
+
+impl Job {
+    pub fn new(play: &Play, settings: Settings) -> Self {
+        let fps = settings.fps.clamp(*FPS_RANGE.start(), *FPS_RANGE.end());
+        Self {
+            play: play.clone(),
+            format: settings.format,
+            width: settings
+                .width
+                .clamp(*WIDTH_RANGE.start(), *WIDTH_RANGE.end()),
+            fps,
+            num_frames: ((play.duration() * fps as f32).ceil() as u32).max(1) + 1,
+            done: 0,
+            encoder: None,
+            output: Output::default(),
+        }
+    }
+
+    /// Fraction of the frames that are done
+    pub fn progress(&self) -> f32 {
+        self.done as f32 / self.num_frames as f32
+    }
+
+    /// Renders and encodes the next frame. Returns the file after the last one.
+    pub fn step(&mut self) -> Result<Option<Vec<u8>>, String> {
+        let time = self.done as f32 / self.fps as f32;
+        let image = raster::render(&Scene::at_time(&self.play, time), self.width);
+        let mut encoder = match self.encoder.take() {
+            Some(encoder) => encoder,
+            None => Encoder::new(self.format, &image, self.num_frames, self.fps, &self.output)?,
+        };
+        encoder.write_frame(image, self.done, self.fps)?;
+        self.done += 1;
+        if self.done < self.num_frames {
+            self.encoder = Some(encoder);
+            return Ok(None);
+        }
+        encoder.finish()?;
+        Ok(Some(self.output.0.take()))
+    }
+}
+
+type Image = (u32, u32, Vec<u8>);
+
+/// Where the encoders write, which can be read after they are finished
+#[derive(Clone, Default)]
+struct Output(Rc<RefCell<Vec<u8>>>);
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+enum Encoder {
+    Gif(gif::Encoder<Output>),
+    Apng(png::Writer<Output>),
+}
+
+fn gif_err(err: impl std::fmt::Display) -> String {
+    format!("Could not make GIF: {err}")
+}
+
+fn png_err(err: png::EncodingError) -> String {
+    format!("Could not make APNG: {err}")
+}
+
+impl Encoder {
+    /// Starts a file of `num_frames` frames as large as `first`
+    fn new(
+        format: Format,
+        first: &Image,
+        num_frames: u32,
+        fps: u32,
+        output: &Output,
+    ) -> Result<Self, String> {
+        let (width, height, _) = *first;
+        match format {
+            Format::Gif => {
+                let mut encoder =
+                    gif::Encoder::new(output.clone(), width as u16, height as u16, &[])
+                        .map_err(gif_err)?;
+                encoder.set_repeat(gif::Repeat::Infinite).map_err(gif_err)?;
+                Ok(Encoder::Gif(encoder))
+            }
+            Format::Apng => {
+                let mut encoder = png::Encoder::new(output.clone(), width, height);
+                encoder.set_color(png::ColorType::Rgba);
+                encoder.set_depth(png::BitDepth::Eight);
+                encoder.set_animated(num_frames, 0).map_err(png_err)?;
+                encoder.set_frame_delay(1, fps as u16).map_err(png_err)?;
+                Ok(Encoder::Apng(encoder.write_header().map_err(png_err)?))
+            }
+        }
+    }
+
+    fn write_frame(
+        &mut self,
+        (width, height, mut rgba): Image,
+        idx: u32,
+        fps: u32,
+    ) -> Result<(), String> {
+        match self {
+            Encoder::Gif(encoder) => {
+                let mut frame = gif::Frame::from_rgba_speed(
+                    width as u16,
+                    height as u16,
+                    &mut rgba,
+                    GIF_QUANTIZE_SPEED,
+                );
+                frame.delay = gif_delay(idx, fps);
+                encoder.write_frame(&frame).map_err(gif_err)
+            }
+            Encoder::Apng(writer) => writer.write_image_data(&rgba).map_err(png_err),
+        }
+    }
+
+    fn finish(self) -> Result<(), String> {
+        match self {
+            Encoder::Gif(encoder) => encoder.into_inner().map(drop).map_err(gif_err),
+            Encoder::Apng(writer) => writer.finish().map_err(png_err),
+        }
+    }
+}
+
+/// How long frame `idx` is shown in a GIF, in hundredths of a second.
+/// The delays are rounded so that the frames stay in time over the whole animation,
+/// instead of the rounding error adding up.
+fn gif_delay(idx: u32, fps: u32) -> u16 {
+    let shown_at = |idx: u32| (100 * idx + fps / 2) / fps;
+    (shown_at(idx + 1) - shown_at(idx)) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_gif_keeps_in_time() {
+        for fps in [7, 15, 24, 30, 50] {
+            let total: u32 = (0..fps).map(|idx| gif_delay(idx, fps) as u32).sum();
+            assert_eq!(total, 100, "{fps} fps");
+        }
+    }
+}
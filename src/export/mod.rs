@@ -0,0 +1,515 @@
+//! Pictures of plays made from the model alone, without egui's painter, so
+//! they can be made headlessly.
+//!
+//! A play is first laid out as a [`Scene`] of simple shapes in pixels, which
+//! each file format then writes out.
+
+pub mod animated;
+pub mod pdf;
+pub mod raster;
+pub mod svg;
+
+use std::borrow::Cow;
+
+use egui::{Color32, FontDefinitions, FontFamily, Pos2, Rect, Vec2};
+
+use crate::model::{
+    ball::goal_posts,
+    field::{markings, Marking},
+    Actor, Glyph, Path, Play, PlayerType, Point, Step,
+};
+
+/// Pixels per meter
+pub const SCALE: f32 = 24.0;
+/// Steps per row in a storyboard
+pub const STORYBOARD_COLUMNS: usize = 3;
+
+const BACKGROUND: Color32 = Color32::WHITE;
+const LINE_COLOR: Color32 = Color32::from_gray(90);
+const TEXT_COLOR: Color32 = Color32::BLACK;
+/// Passes and shots, darker than the ball so they show on white
+const PASS_COLOR: Color32 = Color32::from_rgb(200, 150, 0);
+/// Room around each step of a storyboard, in pixels
+const PADDING: f32 = 16.0;
+/// Height of the step number above each step of a storyboard, in pixels
+const CAPTION: f32 = 24.0;
+/// Radius of a person, in pixels
+const RADIUS: f32 = 10.0;
+
+/// Name and contents of the font egui writes with, so that text looks the
+/// same in every picture as in the editor
+pub(crate) fn default_font() -> (String, Cow<'static, [u8]>) {
+    let mut fonts = FontDefinitions::default();
+    let name = fonts.families[&FontFamily::Proportional][0].clone();
+    let data = fonts
+        .font_data
+        .remove(&name)
+        .expect("egui has a default font");
+    (name, data.font)
+}
+
+/// A simple shape, in pixels from the top left corner of the picture
+#[derive(Debug, Clone, PartialEq)]
+pub enum Item {
+    Line {
+        pts: Vec<Pos2>,
+        width: f32,
+        color: Color32,
+        dashed: bool,
+    },
+    /// A cubic bezier curve
+    Curve {
+        pts: [Pos2; 4],
+        width: f32,
+        color: Color32,
+    },
+    Polygon {
+        pts: Vec<Pos2>,
+        fill: Color32,
+    },
+    Circle {
+        center: Pos2,
+        radius: f32,
+        fill: Color32,
+    },
+    /// A circle outline, with the line centered on `radius`
+    Ring {
+        center: Pos2,
+        radius: f32,
+        width: f32,
+        color: Color32,
+    },
+    Rect {
+        rect: Rect,
+        rounding: f32,
+        fill: Color32,
+    },
+    /// Text centered on `pos`
+    Text {
+        pos: Pos2,
+        text: String,
+        size: f32,
+        color: Color32,
+    },
+}
+
+/// What colors a picture is made with
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Ink {
+    /// The colors of the editor
+    #[default]
+    Color,
+    /// Shades of gray, with defenders drawn hollow so they stand out from
+    /// attackers on a black and white printer
+    BlackAndWhite,
+}
+
+impl Ink {
+    pub const ALL: [Ink; 2] = [Ink::Color, Ink::BlackAndWhite];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Ink::Color => "Color",
+            Ink::BlackAndWhite => "Black and white",
+        }
+    }
+
+    /// How `color` is drawn
+    fn color(&self, color: Color32) -> Color32 {
+        match self {
+            Ink::Color => color,
+            Ink::BlackAndWhite => {
+                let [r, g, b, a] = color.to_srgba_unmultiplied();
+                let gray = 0.3 * r as f32 + 0.59 * g as f32 + 0.11 * b as f32;
+                Color32::from_rgba_unmultiplied(gray as u8, gray as u8, gray as u8, a)
+            }
+        }
+    }
+
+    /// Fill and outline of someone of type `p_type`
+    fn person(&self, p_type: PlayerType) -> (Color32, Option<Color32>) {
+        match self {
+            Ink::Color => (p_type.color(), None),
+            Ink::BlackAndWhite => match p_type {
+                PlayerType::Attacking => (Color32::BLACK, None),
+                PlayerType::Defending | PlayerType::Ball => (Color32::WHITE, Some(Color32::BLACK)),
+                PlayerType::AttackingGoalkeeper => (Color32::from_gray(90), None),
+                PlayerType::DefendingGoalkeeper => (Color32::from_gray(190), None),
+                PlayerType::Referee => (Color32::from_gray(130), None),
+                _ => (Color32::from_gray(150), None),
+            },
+        }
+    }
+
+    /// Color of text written on `fill`
+    fn text_on(&self, fill: Color32) -> Color32 {
+        match self {
+            Ink::Color => Color32::WHITE,
+            Ink::BlackAndWhite if fill.r() > 160 => Color32::BLACK,
+            Ink::BlackAndWhite => Color32::WHITE,
+        }
+    }
+}
+
+/// A picture of a play
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scene {
+    pub size: Vec2,
+    pub background: Color32,
+    pub items: Vec<Item>,
+    pub ink: Ink,
+}
+
+impl Scene {
+    /// Step `frame_idx` of the play as it is when the step starts, with the
+    /// paths and passes during the step
+    pub fn step(play: &Play, frame_idx: usize, ink: Ink) -> Self {
+        let bounds = play.view.bounds();
+        let to_px = |pt: Point| ((pt - bounds.min) * SCALE).to_pos2();
+        let mut scene = Self::new(bounds.size() * SCALE, ink);
+        scene.add_field(play, to_px);
+        scene.add_frame(&play.steps[frame_idx], to_px);
+        scene
+    }
+
+    /// The play as it is `time` seconds in, like it is played in the editor
+    pub fn at_time(play: &Play, time: f32) -> Self {
+        let bounds = play.view.bounds();
+        let to_px = |pt: Point| ((pt - bounds.min) * SCALE).to_pos2();
+        let mut scene = Self::new(bounds.size() * SCALE, Ink::Color);
+        scene.add_field(play, to_px);
+
+        let (frame_idx, frac) = play.step_at(time);
+        let frame = &play.steps[frame_idx];
+        let positions = frame.positions(frac);
+        let ball = frame.ball().zip(frame.ball_at(frac));
+        for (idx, person) in frame.actors.iter().enumerate() {
+            match ball {
+                Some((ball, (pt, height))) if ball == idx => {
+                    scene.add_ball_in_air(person, to_px(pt), height);
+                }
+                _ => scene.add_person(person, to_px(positions[idx])),
+            }
+        }
+        scene
+    }
+
+    /// Every step of the play in a grid, numbered
+    pub fn storyboard(play: &Play, ink: Ink) -> Self {
+        let cell_size = play.view.bounds().size() * SCALE + Vec2::new(0.0, CAPTION);
+        let columns = STORYBOARD_COLUMNS.min(play.steps.len());
+        let rows = (play.steps.len() + columns - 1) / columns;
+        let size = Vec2::new(columns as f32, rows as f32) * (cell_size + Vec2::splat(PADDING))
+            + Vec2::splat(PADDING);
+        let mut scene = Self::new(size, ink);
+        for frame_idx in 0..play.steps.len() {
+            let cell = Vec2::new((frame_idx % columns) as f32, (frame_idx / columns) as f32);
+            let offset = Vec2::splat(PADDING) + cell * (cell_size + Vec2::splat(PADDING));
+            scene.items.push(Item::Text {
+                pos: (offset + Vec2::new(cell_size.x / 2.0, CAPTION / 2.0)).to_pos2(),
+                text: format!("Step {}", frame_idx + 1),
+                size: 16.0,
+                color: TEXT_COLOR,
+            });
+            let step = Self::step(play, frame_idx, ink);
+            scene.append(step, offset + Vec2::new(0.0, CAPTION));
+        }
+        scene
+    }
+
+    fn new(size: Vec2, ink: Ink) -> Self {
+        Self {
+            size,
+            background: BACKGROUND,
+            items: Vec::new(),
+            ink,
+        }
+    }
+
+    /// Adds the items of `other`, moved by `offset`
+    fn append(&mut self, other: Scene, offset: Vec2) {
+        self.items.push(Item::Rect {
+            rect: Rect::from_min_size(offset.to_pos2(), other.size),
+            rounding: 0.0,
+            fill: other.background,
+        });
+        self.items
+            .extend(other.items.into_iter().map(|item| item.translate(offset)));
+    }
+
+    fn add_field(&mut self, play: &Play, to_px: impl Fn(Point) -> Pos2) {
+        for marking in markings(play.view) {
+            match marking {
+                Marking::Line(pts) => self.add_marking(pts, &to_px, false),
+                Marking::Dashed(pts) => self.add_marking(pts, &to_px, true),
+                Marking::Goal(rect) => self.items.push(Item::Rect {
+                    rect: Rect::from_two_pos(to_px(rect.min), to_px(rect.max)),
+                    rounding: 0.0,
+                    fill: self.ink.color(Color32::RED),
+                }),
+            }
+        }
+    }
+
+    fn add_marking(&mut self, pts: Vec<Point>, to_px: impl Fn(Point) -> Pos2, dashed: bool) {
+        self.items.push(Item::Line {
+            pts: pts.into_iter().map(to_px).collect(),
+            width: 1.5,
+            color: LINE_COLOR,
+            dashed,
+        });
+    }
+
+    fn add_frame(&mut self, frame: &Step, to_px: impl Fn(Point) -> Pos2) {
+        for person in &frame.actors {
+            if let Path::Spline(spline) = &person.path {
+                let color = self.ink.color(person.get_color());
+                for segment in spline.segments() {
+                    self.items.push(Item::Curve {
+                        pts: segment.map(&to_px),
+                        width: 2.0,
+                        color,
+                    });
+                }
+                if let Some(dir) = spline.end_direction() {
+                    self.add_arrow_head(to_px(spline.end()), dir, color);
+                }
+            }
+        }
+
+        let pass_color = self.ink.color(PASS_COLOR);
+        for (from, to, _) in frame.pass_lines() {
+            self.add_arrow(to_px(from), to_px(to), pass_color, true);
+        }
+        if let Some((from, to, _, _)) = frame.shot_flight() {
+            let posts = goal_posts(from);
+            self.items.push(Item::Polygon {
+                pts: vec![to_px(from), to_px(posts[0]), to_px(posts[1])],
+                fill: pass_color.gamma_multiply(0.25),
+            });
+            self.add_arrow(to_px(from), to_px(to), pass_color, false);
+        }
+
+        let held_ball = frame.carrier.and(frame.ball());
+        for (idx, person) in frame.actors.iter().enumerate() {
+            if Some(idx) != held_ball {
+                self.add_person(person, to_px(person.path.start()));
+            }
+        }
+        if let (Some(ball), Some((pt, _))) = (held_ball, frame.ball_at(0.0)) {
+            self.add_person(&frame.actors[ball], to_px(pt));
+        }
+    }
+
+    /// The ball `height` meters above `center`, with `height` from [`Step::ball_at`]
+    fn add_ball_in_air(&mut self, ball: &Actor, center: Pos2, height: f32) {
+        let radius = RADIUS * 0.6;
+        self.items.push(Item::Circle {
+            center,
+            radius,
+            fill: Color32::from_black_alpha(80),
+        });
+        let lifted = center - Vec2::new(0.0, height * 3.0);
+        let (fill, outline) = self.ink.person(ball.p_type);
+        let radius = radius * (1.0 + height / 4.0);
+        self.add_glyph(ball.p_type.glyph(), lifted, radius, fill, outline);
+    }
+
+    fn add_arrow(&mut self, from: Pos2, to: Pos2, color: Color32, dashed: bool) {
+        self.items.push(Item::Line {
+            pts: vec![from, to],
+            width: 2.0,
+            color,
+            dashed,
+        });
+        self.add_arrow_head(to, to - from, color);
+    }
+
+    /// A triangle pointing along `dir` with its tip at `tip`
+    fn add_arrow_head(&mut self, tip: Pos2, dir: Vec2, color: Color32) {
+        let dir = dir.normalized();
+        if !dir.is_finite() {
+            return;
+        }
+        let back = tip - dir * 10.0;
+        let side = dir.rot90() * 5.0;
+        self.items.push(Item::Polygon {
+            pts: vec![tip, back + side, back - side],
+            fill: color,
+        });
+    }
+
+    fn add_person(&mut self, person: &Actor, center: Pos2) {
+        let radius = if person.p_type == PlayerType::Ball {
+            RADIUS * 0.6
+        } else {
+            RADIUS
+        };
+        let (fill, outline) = self.ink.person(person.p_type);
+        self.add_glyph(person.p_type.glyph(), center, radius, fill, outline);
+        let text = match person.number {
+            Some(number) => number.to_string(),
+            None => person.label.clone(),
+        };
+        if !text.is_empty() {
+            self.items.push(Item::Text {
+                pos: center,
+                text,
+                size: 11.0,
+                color: self.ink.text_on(fill),
+            });
+        }
+        if !person.player_name.is_empty() {
+            self.items.push(Item::Text {
+                pos: center - Vec2::new(0.0, radius + 7.0),
+                text: person.player_name.clone(),
+                size: 10.0,
+                color: TEXT_COLOR,
+            });
+        }
+    }
+
+    /// The same shapes as the editor draws. Only circles get an `outline`.
+    fn add_glyph(
+        &mut self,
+        glyph: Glyph,
+        center: Pos2,
+        radius: f32,
+        color: Color32,
+        outline: Option<Color32>,
+    ) {
+        let rect = |size: Vec2, rounding: f32| Item::Rect {
+            rect: Rect::from_center_size(center, size),
+            rounding,
+            fill: color,
+        };
+        // Inside the circle, like egui draws it
+        let ring = |color: Color32| {
+            let width = 1.5;
+            Item::Ring {
+                center,
+                radius: radius - width,
+                width,
+                color,
+            }
+        };
+        match glyph {
+            Glyph::Circle => {
+                self.items.push(Item::Circle {
+                    center,
+                    radius,
+                    fill: color,
+                });
+                self.items.extend(outline.map(ring));
+            }
+            Glyph::Square => self
+                .items
+                .push(rect(Vec2::splat(radius * 1.8), radius * 0.3)),
+            Glyph::Striped => {
+                self.items.push(Item::Circle {
+                    center,
+                    radius,
+                    fill: color,
+                });
+                self.items.push(ring(Color32::WHITE));
+            }
+            Glyph::Triangle => {
+                let pts = [-90.0_f32, 30.0, 150.0]
+                    .map(|deg| center + radius * Vec2::angled(deg.to_radians()));
+                self.items.push(Item::Polygon {
+                    pts: pts.to_vec(),
+                    fill: color,
+                });
+            }
+            Glyph::Bar => self
+                .items
+                .push(rect(Vec2::new(radius * 0.6, radius * 2.0), 1.0)),
+            Glyph::Mat => self
+                .items
+                .push(rect(Vec2::new(radius * 3.0, radius * 1.6), 2.0)),
+            Glyph::Frame => {
+                let size = Vec2::splat(radius * 1.8);
+                self.items.push(rect(size, 0.0));
+                let frame = Rect::from_center_size(center, size);
+                for pts in [
+                    [frame.left_top(), frame.right_bottom()],
+                    [frame.right_top(), frame.left_bottom()],
+                ] {
+                    self.items.push(Item::Line {
+                        pts: pts.to_vec(),
+                        width: 1.0,
+                        color: Color32::WHITE,
+                        dashed: false,
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl Item {
+    fn translate(self, d: Vec2) -> Self {
+        match self {
+            Item::Line {
+                pts,
+                width,
+                color,
+                dashed,
+            } => Item::Line {
+                pts: pts.into_iter().map(|pt| pt + d).collect(),
+                width,
+                color,
+                dashed,
+            },
+            Item::Curve { pts, width, color } => Item::Curve {
+                pts: pts.map(|pt| pt + d),
+                width,
+                color,
+            },
+            Item::Polygon { pts, fill } => Item::Polygon {
+                pts: pts.into_iter().map(|pt| pt + d).collect(),
+                fill,
+            },
+            Item::Circle {
+                center,
+                radius,
+                fill,
+            } => Item::Circle {
+                center: center + d,
+                radius,
+                fill,
+            },
+            Item::Ring {
+                center,
+                radius,
+                width,
+                color,
+            } => Item::Ring {
+                center: center + d,
+                radius,
+                width,
+                color,
+            },
+            Item::Rect {
+                rect,
+                rounding,
+                fill,
+            } => Item::Rect {
+                rect: rect.translate(d),
+                rounding,
+                fill,
+            },
+            Item::Text {
+                pos,
+                text,
+                size,
+                color,
+            } => Item::Text {
+                pos: pos + d,
+                text,
+                size,
+                color,
+            },
+        }
+    }
+}
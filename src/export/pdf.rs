@@ -0,0 +1,575 @@
+//! Printable playbooks: a title page per play, followed by its steps.
+
+use std::{collections::BTreeMap, io::Write as _};
+
+use ab_glyph::{Font, FontRef};
+use egui::{Color32, Pos2, Vec2};
+use flate2::{write::ZlibEncoder, Compression};
+use pdf_writer::{
+    types::{CidFontType, FontFlags, SystemInfo, UnicodeCmap},
+    Content, Filter, Finish, Name, Pdf, Rect, Ref, Str, TextStr,
+};
+
+use super::{default_font, Ink, Item, Scene};
+use crate::library::PlayEntry;
+
+pub const MIME: &str = "application/pdf";
+pub const EXTENSION: &str = "pdf";
+
+/// Space around everything on a page, in points
+const MARGIN: f32 = 42.0;
+const STEPS_PER_PAGE: usize = 2;
+/// The least height of the diagram of a step, in points, however long its notes are
+const MIN_DIAGRAM_HEIGHT: f32 = 120.0;
+/// Font sizes, in points
+const TITLE_SIZE: f32 = 24.0;
+const HEADING_SIZE: f32 = 13.0;
+const TEXT_SIZE: f32 = 11.0;
+/// Distance between lines, relative to the font size
+const LINE_SPACING: f32 = 1.4;
+/// How far the control points of a quarter circle are from its ends, relative to the radius
+const KAPPA: f32 = 0.5523;
+/// Name of the one font on every page
+const FONT: Name<'static> = Name(b"F1");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum PageSize {
+    A4,
+    A5,
+    Letter,
+}
+
+impl PageSize {
+    pub const ALL: [PageSize; 3] = [PageSize::A4, PageSize::A5, PageSize::Letter];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PageSize::A4 => "A4",
+            PageSize::A5 => "A5",
+            PageSize::Letter => "Letter",
+        }
+    }
+
+    /// Width and height in points, standing up
+    fn size(&self) -> Vec2 {
+        match self {
+            PageSize::A4 => Vec2::new(595.3, 841.9),
+            PageSize::A5 => Vec2::new(419.5, 595.3),
+            PageSize::Letter => Vec2::new(612.0, 792.0),
+        }
+    }
+}
+
+/// How the playbook is printed
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub page_size: PageSize,
+    pub ink: Ink,
+    /// Whether the coach notes are printed under each step
+    pub notes: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            page_size: PageSize::A4,
+            ink: Ink::Color,
+            notes: true,
+        }
+    }
+}
+
+/// A PDF with every play in `plays`, in order
+pub fn playbook(plays: &[&PlayEntry], settings: Settings) -> Result<Vec<u8>, String> {
+    if plays.is_empty() {
+        return Err("Pick at least one play for the playbook".to_owned());
+    }
+
+    let (font_name, font_data) = default_font();
+    let font = FontRef::try_from_slice(&font_data).map_err(|err| err.to_string())?;
+    let mut book = Book {
+        font,
+        size: settings.page_size.size(),
+        pages: Vec::new(),
+        glyphs: BTreeMap::new(),
+    };
+
+    for play in plays {
+        let mut page = book.new_page();
+        book.title(&mut page, play);
+        book.pages.push(page);
+
+        let steps = &play.play.steps;
+        let slot_height = (book.size.y - 2.0 * MARGIN) / STEPS_PER_PAGE as f32;
+        for (page_idx, frames) in steps.chunks(STEPS_PER_PAGE).enumerate() {
+            let mut page = book.new_page();
+            for (slot, frame) in frames.iter().enumerate() {
+                let frame_idx = page_idx * STEPS_PER_PAGE + slot;
+                let caption = format!("Step {} · {:.1} s", frame_idx + 1, frame.duration);
+                let notes = if settings.notes {
+                    frame.notes.trim()
+                } else {
+                    ""
+                };
+                let scene = Scene::step(&play.play, frame_idx, settings.ink);
+                let top = MARGIN + slot as f32 * slot_height;
+                book.step(&mut page, &caption, notes, &scene, top, slot_height);
+            }
+            book.pages.push(page);
+        }
+    }
+
+    let title = match plays {
+        [play] => play.name.as_str(),
+        _ => "Playbook",
+    };
+    Ok(book.finish(title, &font_name, &font_data))
+}
+
+/// A playbook being written
+struct Book<'a> {
+    font: FontRef<'a>,
+    /// Of every page, in points
+    size: Vec2,
+    pages: Vec<Content>,
+    /// Every glyph written, and the character it shows
+    glyphs: BTreeMap<u16, char>,
+}
+
+impl Book<'_> {
+    /// An empty page, measured in points from its top left corner
+    fn new_page(&self) -> Content {
+        let mut page = Content::new();
+        page.transform([1.0, 0.0, 0.0, -1.0, 0.0, self.size.y]);
+        page
+    }
+
+    /// In points, for a font of `size` points
+    fn width(&self, text: &str, size: f32) -> f32 {
+        let units: f32 = text
+            .chars()
+            .map(|ch| self.font.h_advance_unscaled(self.font.glyph_id(ch)))
+            .sum();
+        units * self.em(size)
+    }
+
+    /// Points per font unit
+    fn em(&self, size: f32) -> f32 {
+        size / self.font.units_per_em().unwrap_or(1000.0)
+    }
+
+    /// `text` broken into lines no wider than `width` points. Line breaks in
+    /// the text are kept, and words too wide for a line are broken anywhere.
+    fn wrap(&self, text: &str, size: f32, width: f32) -> Vec<String> {
+        let mut lines = Vec::new();
+        for paragraph in text.lines() {
+            let mut line = String::new();
+            for word in paragraph.split_whitespace() {
+                let longer = if line.is_empty() {
+                    word.to_owned()
+                } else {
+                    format!("{line} {word}")
+                };
+                if self.width(&longer, size) <= width {
+                    line = longer;
+                    continue;
+                }
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                for ch in word.chars() {
+                    if !line.is_empty() && self.width(&format!("{line}{ch}"), size) > width {
+                        lines.push(std::mem::take(&mut line));
+                    }
+                    line.push(ch);
+                }
+            }
+            lines.push(line);
+        }
+        lines
+    }
+
+    /// The first `max` of `lines`, with an ellipsis at the end if any are left out
+    fn clip(&self, mut lines: Vec<String>, max: usize, size: f32, width: f32) -> Vec<String> {
+        if lines.len() <= max {
+            return lines;
+        }
+        lines.truncate(max);
+        if let Some(last) = lines.last_mut() {
+            while !last.is_empty() && self.width(&format!("{last}…"), size) > width {
+                last.pop();
+            }
+            last.push('…');
+        }
+        lines
+    }
+
+    /// Writes `text` with its baseline starting at `pos`
+    fn text(&mut self, page: &mut Content, text: &str, size: f32, pos: Pos2, color: Color32) {
+        let mut encoded = Vec::new();
+        for ch in text.chars() {
+            let glyph = self.font.glyph_id(ch).0;
+            self.glyphs.entry(glyph).or_insert(ch);
+            encoded.extend(glyph.to_be_bytes());
+        }
+        set_fill(page, color);
+        page.begin_text();
+        page.set_font(FONT, 1.0);
+        // Upside down, since the page is
+        page.set_text_matrix([size, 0.0, 0.0, -size, pos.x, pos.y]);
+        page.show(Str(&encoded));
+        page.end_text();
+    }
+
+    /// Writes `lines` from `top` and down, going on at the top of a new page
+    /// when `page` is full. Returns where the text ends.
+    fn paragraph(&mut self, page: &mut Content, lines: &[String], size: f32, top: f32) -> f32 {
+        let line_height = size * LINE_SPACING;
+        let mut y = top;
+        for line in lines {
+            if y + line_height > self.size.y - MARGIN {
+                let full = std::mem::replace(page, self.new_page());
+                self.pages.push(full);
+                y = MARGIN;
+            }
+            y += line_height;
+            self.text(page, line, size, Pos2::new(MARGIN, y), Color32::BLACK);
+        }
+        y
+    }
+
+    /// The name of the play, who made it, its tags and its description.
+    /// A long description goes on over more pages, leaving the last in `page`.
+    fn title(&mut self, page: &mut Content, play: &PlayEntry) {
+        let width = self.size.x - 2.0 * MARGIN;
+        let name = self.wrap(&play.name, TITLE_SIZE, width);
+        let mut y = self.paragraph(page, &name, TITLE_SIZE, MARGIN);
+
+        let mut details = Vec::new();
+        if !play.author.is_empty() {
+            details.push(format!("By {}", play.author));
+        }
+        let steps = play.play.steps.len();
+        details.push(format!(
+            "{steps} {}, {:.1} s",
+            if steps == 1 { "step" } else { "steps" },
+            play.play.duration()
+        ));
+        if !play.tags.is_empty() {
+            details.push(format!("Tags: {}", play.tags.join(", ")));
+        }
+        y = self.paragraph(page, &details, TEXT_SIZE, y + 8.0);
+
+        let description = play.description.trim();
+        if !description.is_empty() {
+            let description = self.wrap(description, TEXT_SIZE, width);
+            self.paragraph(page, &description, TEXT_SIZE, y + 16.0);
+        }
+    }
+
+    /// One step of a play, fitted between `top` and `top + height`, with the
+    /// caption above and the notes below. Notes too long to fit are cut short.
+    fn step(
+        &mut self,
+        page: &mut Content,
+        caption: &str,
+        notes: &str,
+        scene: &Scene,
+        top: f32,
+        height: f32,
+    ) {
+        let width = self.size.x - 2.0 * MARGIN;
+        let y = self.paragraph(page, &[caption.to_owned()], HEADING_SIZE, top);
+        // Leave room between steps
+        let below = top + height - y - 24.0;
+
+        let line_height = TEXT_SIZE * LINE_SPACING;
+        let notes = if notes.is_empty() {
+            Vec::new()
+        } else {
+            let max = ((below - MIN_DIAGRAM_HEIGHT) / line_height).max(0.0) as usize;
+            self.clip(self.wrap(notes, TEXT_SIZE, width), max, TEXT_SIZE, width)
+        };
+        let notes_height = notes.len() as f32 * line_height;
+
+        let room = Vec2::new(width, below - notes_height);
+        let scale = (room.x / scene.size.x).min(room.y / scene.size.y).max(0.0);
+        let origin = Pos2::new(MARGIN + (width - scene.size.x * scale) / 2.0, y + 6.0);
+        self.scene(page, scene, origin, scale);
+        self.paragraph(page, &notes, TEXT_SIZE, origin.y + scene.size.y * scale);
+    }
+
+    /// Draws `scene` with its top left corner at `origin`, `scale` points per pixel
+    fn scene(&mut self, page: &mut Content, scene: &Scene, origin: Pos2, scale: f32) {
+        page.save_state();
+        page.transform([scale, 0.0, 0.0, scale, origin.x, origin.y]);
+        set_fill(page, scene.background);
+        page.rect(0.0, 0.0, scene.size.x, scene.size.y);
+        page.fill_nonzero();
+
+        for item in &scene.items {
+            match item {
+                Item::Line {
+                    pts,
+                    width,
+                    color,
+                    dashed,
+                } => {
+                    if *dashed {
+                        page.set_dash_pattern([width * 3.0, width * 3.0], 0.0);
+                    }
+                    move_through(page, pts);
+                    set_stroke(page, *width, *color);
+                    page.stroke();
+                    if *dashed {
+                        page.set_dash_pattern([], 0.0);
+                    }
+                }
+                Item::Curve { pts, width, color } => {
+                    page.move_to(pts[0].x, pts[0].y);
+                    page.cubic_to(pts[1].x, pts[1].y, pts[2].x, pts[2].y, pts[3].x, pts[3].y);
+                    set_stroke(page, *width, *color);
+                    page.stroke();
+                }
+                Item::Polygon { pts, fill } => {
+                    move_through(page, pts);
+                    page.close_path();
+                    set_fill(page, *fill);
+                    page.fill_nonzero();
+                }
+                Item::Circle {
+                    center,
+                    radius,
+                    fill,
+                } => {
+                    circle(page, *center, *radius);
+                    set_fill(page, *fill);
+                    page.fill_nonzero();
+                }
+                Item::Ring {
+                    center,
+                    radius,
+                    width,
+                    color,
+                } => {
+                    circle(page, *center, *radius);
+                    set_stroke(page, *width, *color);
+                    page.stroke();
+                }
+                Item::Rect { rect, fill, .. } => {
+                    page.rect(rect.min.x, rect.min.y, rect.width(), rect.height());
+                    set_fill(page, *fill);
+                    page.fill_nonzero();
+                }
+                Item::Text {
+                    pos,
+                    text,
+                    size,
+                    color,
+                } => {
+                    let middle = (self.font.ascent_unscaled() + self.font.descent_unscaled()) / 2.0;
+                    let start = Pos2::new(
+                        pos.x - self.width(text, *size) / 2.0,
+                        pos.y + middle * self.em(*size),
+                    );
+                    self.text(page, text, *size, start, *color);
+                }
+            }
+        }
+        page.restore_state();
+    }
+
+    /// The finished file, with the font embedded
+    fn finish(self, title: &str, font_name: &str, font_data: &[u8]) -> Vec<u8> {
+        let catalog_id = Ref::new(1);
+        let page_tree_id = Ref::new(2);
+        let font_id = Ref::new(3);
+        let cid_font_id = Ref::new(4);
+        let descriptor_id = Ref::new(5);
+        let font_file_id = Ref::new(6);
+        let cmap_id = Ref::new(7);
+        let info_id = Ref::new(8);
+        // Then a page and its contents for every page
+        let page_ids = (0..self.pages.len() as i32).map(|idx| Ref::new(9 + 2 * idx));
+
+        let mut pdf = Pdf::new();
+        pdf.catalog(catalog_id).pages(page_tree_id);
+        pdf.document_info(info_id)
+            .title(TextStr(title))
+            .creator(TextStr("Jugge"));
+        pdf.pages(page_tree_id)
+            .kids(page_ids.clone())
+            .count(self.pages.len() as i32);
+        for (page_id, content) in page_ids.zip(self.pages) {
+            let content_id = Ref::new(page_id.get() + 1);
+            let mut page = pdf.page(page_id);
+            page.media_box(Rect::new(0.0, 0.0, self.size.x, self.size.y));
+            page.parent(page_tree_id);
+            page.contents(content_id);
+            page.resources().fonts().pair(FONT, font_id);
+            page.finish();
+            pdf.stream(content_id, &deflate(&content.finish()))
+                .filter(Filter::FlateDecode);
+        }
+
+        // The font is embedded whole, and text refers to its glyphs directly
+        let base_font = Name(font_name.as_bytes());
+        let system_info = SystemInfo {
+            registry: Str(b"Adobe"),
+            ordering: Str(b"Identity"),
+            supplement: 0,
+        };
+        pdf.type0_font(font_id)
+            .base_font(base_font)
+            .encoding_predefined(Name(b"Identity-H"))
+            .descendant_font(cid_font_id)
+            .to_unicode(cmap_id);
+
+        let to_pdf_units = 1000.0 / self.font.units_per_em().unwrap_or(1000.0);
+        let mut cid_font = pdf.cid_font(cid_font_id);
+        cid_font
+            .subtype(CidFontType::Type2)
+            .base_font(base_font)
+            .system_info(system_info)
+            .font_descriptor(descriptor_id)
+            .cid_to_gid_map_predefined(Name(b"Identity"));
+        let mut widths = cid_font.widths();
+        for &glyph in self.glyphs.keys() {
+            let advance = self.font.h_advance_unscaled(ab_glyph::GlyphId(glyph));
+            widths.consecutive(glyph, [advance * to_pdf_units]);
+        }
+        widths.finish();
+        cid_font.finish();
+
+        let ascent = self.font.ascent_unscaled() * to_pdf_units;
+        let descent = self.font.descent_unscaled() * to_pdf_units;
+        pdf.font_descriptor(descriptor_id)
+            .name(base_font)
+            .flags(FontFlags::NON_SYMBOLIC)
+            .bbox(Rect::new(0.0, descent, 1000.0, ascent))
+            .italic_angle(0.0)
+            .ascent(ascent)
+            .descent(descent)
+            .cap_height(ascent)
+            .stem_v(80.0)
+            .font_file2(font_file_id);
+        pdf.stream(font_file_id, &deflate(font_data))
+            .filter(Filter::FlateDecode)
+            .pair(Name(b"Length1"), font_data.len() as i32);
+
+        let mut cmap = UnicodeCmap::new(Name(b"Custom"), system_info);
+        for (&glyph, &ch) in &self.glyphs {
+            cmap.pair(glyph, ch);
+        }
+        pdf.cmap(cmap_id, &cmap.finish());
+
+        pdf.finish()
+    }
+}
+
+fn move_through(page: &mut Content, pts: &[Pos2]) {
+    for (idx, pt) in pts.iter().enumerate() {
+        if idx == 0 {
+            page.move_to(pt.x, pt.y);
+        } else {
+            page.line_to(pt.x, pt.y);
+        }
+    }
+}
+
+/// Four quarter circles of bezier curves
+fn circle(page: &mut Content, center: Pos2, radius: f32) {
+    let k = radius * KAPPA;
+    let (x, y, r) = (center.x, center.y, radius);
+    page.move_to(x + r, y);
+    page.cubic_to(x + r, y + k, x + k, y + r, x, y + r);
+    page.cubic_to(x - k, y + r, x - r, y + k, x - r, y);
+    page.cubic_to(x - r, y - k, x - k, y - r, x, y - r);
+    page.cubic_to(x + k, y - r, x + r, y - k, x + r, y);
+    page.close_path();
+}
+
+/// `color` as if drawn on white paper, since plain PDF colors have no opacity
+fn paper_rgb(color: Color32) -> [f32; 3] {
+    // Premultiplied, so what is missing is white showing through
+    let white = 255.0 - color.a() as f32;
+    [color.r(), color.g(), color.b()].map(|c| (c as f32 + white) / 255.0)
+}
+
+fn set_fill(page: &mut Content, color: Color32) {
+    let [r, g, b] = paper_rgb(color);
+    page.set_fill_rgb(r, g, b);
+}
+
+fn set_stroke(page: &mut Content, width: f32, color: Color32) {
+    let [r, g, b] = paper_rgb(color);
+    page.set_stroke_rgb(r, g, b);
+    page.set_line_width(width);
+}
+
+fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    // Writing to a vector cannot fail
+    let _ = encoder.write_all(data);
+    encoder.finish().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Play;
+
+    fn book(font_data: &[u8]) -> Book<'_> {
+        Book {
+            font: FontRef::try_from_slice(font_data).unwrap(),
+            size: PageSize::A5.size(),
+            pages: Vec::new(),
+            glyphs: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn a_long_description_goes_on_over_more_pages() {
+        let (_, font_data) = default_font();
+        let mut book = book(&font_data);
+        let mut play = PlayEntry::new("Long", "", Play::default());
+        play.description = "Run to the left and wait for the ball. ".repeat(200);
+
+        let mut page = book.new_page();
+        book.title(&mut page, &play);
+
+        assert!(!book.pages.is_empty());
+    }
+
+    #[test]
+    fn long_notes_are_cut_short_with_an_ellipsis() {
+        let (_, font_data) = default_font();
+        let book = book(&font_data);
+        let width = 100.0;
+        let lines = book.wrap(&"word ".repeat(100), TEXT_SIZE, width);
+
+        let clipped = book.clip(lines, 3, TEXT_SIZE, width);
+
+        assert_eq!(clipped.len(), 3);
+        assert!(clipped[2].ends_with('…'));
+        assert_ne!(book.font.glyph_id('…').0, 0, "the font has an ellipsis");
+        assert!(clipped
+            .iter()
+            .all(|line| book.width(line, TEXT_SIZE) <= width));
+    }
+
+    #[test]
+    fn words_too_wide_for_a_line_are_broken() {
+        let (_, font_data) = default_font();
+        let book = book(&font_data);
+        let width = 100.0;
+
+        let lines = book.wrap(&"w".repeat(100), TEXT_SIZE, width);
+
+        assert!(lines.len() > 1);
+        assert!(lines
+            .iter()
+            .all(|line| book.width(line, TEXT_SIZE) <= width));
+    }
+}
@@ -0,0 +1,228 @@
+//! Draws scenes into pixels on the CPU, so no GPU is needed.
+
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont};
+use egui::{Color32, Pos2};
+use tiny_skia::{
+    FillRule, Paint, PathBuilder, Pixmap, PremultipliedColorU8, Rect, Stroke, StrokeDash, Transform,
+};
+
+use super::{default_font, Item, Scene};
+
+/// Draws `scene` `width` pixels wide, keeping its aspect ratio.
+/// Returns the size and the pixels as RGBA, row by row.
+pub fn render(scene: &Scene, width: u32) -> (u32, u32, Vec<u8>) {
+    let scale = width as f32 / scene.size.x;
+    let height = (scene.size.y * scale).round().max(1.0) as u32;
+    let mut pixmap = Pixmap::new(width.max(1), height).expect("the picture is not empty");
+    pixmap.fill(color(scene.background));
+
+    let (_, font_data) = default_font();
+    let font = FontRef::try_from_slice(&font_data).expect("egui's default font is valid");
+
+    let transform = Transform::from_scale(scale, scale);
+    for item in &scene.items {
+        draw_item(&mut pixmap, &font, item, transform, scale);
+    }
+
+    let pixels = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let pixel = pixel.demultiply();
+            [pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()]
+        })
+        .collect();
+    (pixmap.width(), pixmap.height(), pixels)
+}
+
+fn draw_item(
+    pixmap: &mut Pixmap,
+    font: &FontRef<'_>,
+    item: &Item,
+    transform: Transform,
+    scale: f32,
+) {
+    match item {
+        Item::Line {
+            pts,
+            width,
+            color: c,
+            dashed,
+        } => {
+            let mut pb = PathBuilder::new();
+            move_through(&mut pb, pts);
+            let mut stroke = Stroke {
+                width: *width,
+                line_join: tiny_skia::LineJoin::Round,
+                ..Default::default()
+            };
+            if *dashed {
+                stroke.dash = StrokeDash::new(vec![width * 3.0, width * 3.0], 0.0);
+            }
+            if let Some(path) = pb.finish() {
+                pixmap.stroke_path(&path, &paint(*c), &stroke, transform, None);
+            }
+        }
+        Item::Curve {
+            pts,
+            width,
+            color: c,
+        } => {
+            let mut pb = PathBuilder::new();
+            pb.move_to(pts[0].x, pts[0].y);
+            pb.cubic_to(pts[1].x, pts[1].y, pts[2].x, pts[2].y, pts[3].x, pts[3].y);
+            let stroke = Stroke {
+                width: *width,
+                ..Default::default()
+            };
+            if let Some(path) = pb.finish() {
+                pixmap.stroke_path(&path, &paint(*c), &stroke, transform, None);
+            }
+        }
+        Item::Polygon { pts, fill } => {
+            let mut pb = PathBuilder::new();
+            move_through(&mut pb, pts);
+            pb.close();
+            if let Some(path) = pb.finish() {
+                pixmap.fill_path(&path, &paint(*fill), FillRule::Winding, transform, None);
+            }
+        }
+        Item::Circle {
+            center,
+            radius,
+            fill,
+        } => {
+            if let Some(path) = PathBuilder::from_circle(center.x, center.y, *radius) {
+                pixmap.fill_path(&path, &paint(*fill), FillRule::Winding, transform, None);
+            }
+        }
+        Item::Ring {
+            center,
+            radius,
+            width,
+            color,
+        } => {
+            if let Some(path) = PathBuilder::from_circle(center.x, center.y, *radius) {
+                let stroke = Stroke {
+                    width: *width,
+                    ..Default::default()
+                };
+                pixmap.stroke_path(&path, &paint(*color), &stroke, transform, None);
+            }
+        }
+        Item::Rect {
+            rect,
+            rounding,
+            fill,
+        } => {
+            let path = if *rounding > 0.0 {
+                rounded_rect(*rect, *rounding)
+            } else {
+                Rect::from_ltrb(rect.min.x, rect.min.y, rect.max.x, rect.max.y)
+                    .map(PathBuilder::from_rect)
+            };
+            if let Some(path) = path {
+                pixmap.fill_path(&path, &paint(*fill), FillRule::Winding, transform, None);
+            }
+        }
+        Item::Text {
+            pos,
+            text,
+            size,
+            color: c,
+        } => draw_text(pixmap, font, *pos * scale, text, size * scale, *c),
+    }
+}
+
+fn move_through(pb: &mut PathBuilder, pts: &[Pos2]) {
+    for (idx, pt) in pts.iter().enumerate() {
+        if idx == 0 {
+            pb.move_to(pt.x, pt.y);
+        } else {
+            pb.line_to(pt.x, pt.y);
+        }
+    }
+}
+
+fn rounded_rect(rect: egui::Rect, rounding: f32) -> Option<tiny_skia::Path> {
+    let r = rounding.min(rect.width() / 2.0).min(rect.height() / 2.0);
+    let (l, t, rt, b) = (rect.min.x, rect.min.y, rect.max.x, rect.max.y);
+    let mut pb = PathBuilder::new();
+    pb.move_to(l + r, t);
+    pb.line_to(rt - r, t);
+    pb.quad_to(rt, t, rt, t + r);
+    pb.line_to(rt, b - r);
+    pb.quad_to(rt, b, rt - r, b);
+    pb.line_to(l + r, b);
+    pb.quad_to(l, b, l, b - r);
+    pb.line_to(l, t + r);
+    pb.quad_to(l, t, l + r, t);
+    pb.close();
+    pb.finish()
+}
+
+/// Writes `text` centered on `pos`, in pixels
+fn draw_text(
+    pixmap: &mut Pixmap,
+    font: &FontRef<'_>,
+    pos: Pos2,
+    text: &str,
+    size: f32,
+    c: Color32,
+) {
+    let font = font.as_scaled(PxScale::from(size));
+    let width: f32 = text
+        .chars()
+        .map(|ch| font.h_advance(font.glyph_id(ch)))
+        .sum();
+    let baseline = pos.y + (font.ascent() + font.descent()) / 2.0;
+    let mut x = pos.x - width / 2.0;
+
+    let [r, g, b, a] = c.to_srgba_unmultiplied();
+    let (pixmap_width, pixmap_height) = (pixmap.width(), pixmap.height());
+    let pixels = pixmap.pixels_mut();
+    for ch in text.chars() {
+        let glyph_id = font.glyph_id(ch);
+        let glyph = glyph_id.with_scale_and_position(size, ab_glyph::point(x, baseline));
+        x += font.h_advance(glyph_id);
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outlined.px_bounds();
+        outlined.draw(|gx, gy, coverage| {
+            let px = bounds.min.x as i64 + gx as i64;
+            let py = bounds.min.y as i64 + gy as i64;
+            if px < 0 || py < 0 || px >= pixmap_width as i64 || py >= pixmap_height as i64 {
+                return;
+            }
+            let pixel = &mut pixels[py as usize * pixmap_width as usize + px as usize];
+            *pixel = blend(*pixel, [r, g, b], coverage * a as f32 / 255.0);
+        });
+    }
+}
+
+/// `color` drawn over `dst` with opacity `alpha`
+fn blend(dst: PremultipliedColorU8, color: [u8; 3], alpha: f32) -> PremultipliedColorU8 {
+    let alpha = alpha.clamp(0.0, 1.0);
+    let mix = |src: u8, dst: u8| (src as f32 * alpha + dst as f32 * (1.0 - alpha)).round() as u8;
+    let out_alpha = mix(255, dst.alpha());
+    PremultipliedColorU8::from_rgba(
+        mix(color[0], dst.red()).min(out_alpha),
+        mix(color[1], dst.green()).min(out_alpha),
+        mix(color[2], dst.blue()).min(out_alpha),
+        out_alpha,
+    )
+    .unwrap_or(dst)
+}
+
+fn color(c: Color32) -> tiny_skia::Color {
+    let [r, g, b, a] = c.to_srgba_unmultiplied();
+    tiny_skia::Color::from_rgba8(r, g, b, a)
+}
+
+fn paint(c: Color32) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color(color(c));
+    paint.anti_alias = true;
+    paint
+}
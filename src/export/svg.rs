@@ -0,0 +1,170 @@
+//! Standalone SVG pictures of plays.
+
+use std::fmt::Write as _;
+
+use egui::{Color32, Pos2};
+
+use super::{Ink, Item, Scene};
+use crate::model::Play;
+
+pub const MIME: &str = "image/svg+xml";
+pub const EXTENSION: &str = "svg";
+
+/// Step `frame_idx` of the play
+pub fn step(play: &Play, frame_idx: usize) -> String {
+    to_svg(&Scene::step(play, frame_idx, Ink::Color))
+}
+
+/// Every step of the play in a grid
+pub fn storyboard(play: &Play) -> String {
+    to_svg(&Scene::storyboard(play, Ink::Color))
+}
+
+pub fn to_svg(scene: &Scene) -> String {
+    let mut svg = String::new();
+    let (width, height) = (scene.size.x, scene.size.y);
+    // Writing to a string cannot fail
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif">"#
+    );
+    let _ = writeln!(
+        svg,
+        r#"<rect width="{width}" height="{height}" {}/>"#,
+        fill(scene.background)
+    );
+    for item in &scene.items {
+        write_item(&mut svg, item);
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn write_item(svg: &mut String, item: &Item) {
+    let _ = match item {
+        Item::Line {
+            pts,
+            width,
+            color,
+            dashed,
+        } => {
+            let dash = if *dashed {
+                format!(r#" stroke-dasharray="{} {}""#, width * 3.0, width * 3.0)
+            } else {
+                String::new()
+            };
+            writeln!(
+                svg,
+                r#"<polyline points="{}" fill="none" {}{dash}/>"#,
+                points(pts),
+                stroke(*width, *color)
+            )
+        }
+        Item::Curve { pts, width, color } => writeln!(
+            svg,
+            r#"<path d="M {} C {} {} {}" fill="none" {}/>"#,
+            point(pts[0]),
+            point(pts[1]),
+            point(pts[2]),
+            point(pts[3]),
+            stroke(*width, *color)
+        ),
+        Item::Polygon { pts, fill: color } => {
+            writeln!(
+                svg,
+                r#"<polygon points="{}" {}/>"#,
+                points(pts),
+                fill(*color)
+            )
+        }
+        Item::Circle {
+            center,
+            radius,
+            fill: color,
+        } => writeln!(
+            svg,
+            r#"<circle cx="{}" cy="{}" r="{radius}" {}/>"#,
+            center.x,
+            center.y,
+            fill(*color)
+        ),
+        Item::Ring {
+            center,
+            radius,
+            width,
+            color,
+        } => writeln!(
+            svg,
+            r#"<circle cx="{}" cy="{}" r="{radius}" fill="none" {}/>"#,
+            center.x,
+            center.y,
+            stroke(*width, *color)
+        ),
+        Item::Rect {
+            rect,
+            rounding,
+            fill: color,
+        } => writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{rounding}" {}/>"#,
+            rect.min.x,
+            rect.min.y,
+            rect.width(),
+            rect.height(),
+            fill(*color)
+        ),
+        Item::Text {
+            pos,
+            text,
+            size,
+            color,
+        } => writeln!(
+            svg,
+            r#"<text x="{}" y="{}" font-size="{size}" text-anchor="middle" dominant-baseline="central" {}>{}</text>"#,
+            pos.x,
+            pos.y,
+            fill(*color),
+            escape(text)
+        ),
+    };
+}
+
+fn point(pt: Pos2) -> String {
+    format!("{},{}", pt.x, pt.y)
+}
+
+fn points(pts: &[Pos2]) -> String {
+    pts.iter()
+        .map(|&pt| point(pt))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The color without opacity, since colors are premultiplied
+fn hex(color: Color32) -> String {
+    let [r, g, b, _] = color.to_srgba_unmultiplied();
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+fn fill(color: Color32) -> String {
+    if color.is_opaque() {
+        format!(r#"fill="{}""#, hex(color))
+    } else {
+        let opacity = color.a() as f32 / 255.0;
+        format!(r#"fill="{}" fill-opacity="{opacity}""#, hex(color))
+    }
+}
+
+fn stroke(width: f32, color: Color32) -> String {
+    format!(
+        r#"stroke="{}" stroke-width="{width}" stroke-linejoin="round""#,
+        hex(color)
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
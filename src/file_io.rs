@@ -0,0 +1,109 @@
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+/// A file picked by the user
+pub struct OpenedFile {
+    pub name: String,
+    pub contents: Vec<u8>,
+}
+
+/// Opens and saves files. Uses file dialogs on native, and uploads and
+/// downloads on the web.
+pub struct FileIo {
+    tx: Sender<Result<OpenedFile, String>>,
+    rx: Receiver<Result<OpenedFile, String>>,
+}
+
+impl Default for FileIo {
+    fn default() -> Self {
+        let (tx, rx) = channel();
+        Self { tx, rx }
+    }
+}
+
+impl FileIo {
+    /// Lets the user pick a file with one of the given extensions.
+    /// The file is returned from [`Self::poll_opened`] when it has been read.
+    pub fn open(&self, ctx: &egui::Context, filter_name: &str, extensions: &[&str]) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter(filter_name, extensions)
+            .pick_file()
+        {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let opened = std::fs::read(&path)
+                .map(|contents| OpenedFile { name, contents })
+                .map_err(|err| format!("Could not read {}: {err}", path.display()));
+            self.tx.send(opened).ok();
+            ctx.request_repaint();
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let dialog = rfd::AsyncFileDialog::new().add_filter(filter_name, extensions);
+            let tx = self.tx.clone();
+            let ctx = ctx.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Some(handle) = dialog.pick_file().await {
+                    let contents = handle.read().await;
+                    tx.send(Ok(OpenedFile {
+                        name: handle.file_name(),
+                        contents,
+                    }))
+                    .ok();
+                    ctx.request_repaint();
+                }
+            });
+        }
+    }
+
+    /// A file the user has opened, if any
+    pub fn poll_opened(&self) -> Option<Result<OpenedFile, String>> {
+        self.rx.try_recv().ok()
+    }
+
+    /// Saves `contents` to a file chosen by the user, or downloads it on the web
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&self, file_name: &str, _mime: &str, contents: &[u8]) -> Result<(), String> {
+        let mut dialog = rfd::FileDialog::new().set_file_name(file_name);
+        if let Some((_, extension)) = file_name.rsplit_once('.') {
+            dialog = dialog.add_filter(extension, &[extension]);
+        }
+        match dialog.save_file() {
+            Some(path) => std::fs::write(&path, contents)
+                .map_err(|err| format!("Could not write {}: {err}", path.display())),
+            None => Ok(()),
+        }
+    }
+
+    /// Saves `contents` to a file chosen by the user, or downloads it on the web
+    #[cfg(target_arch = "wasm32")]
+    pub fn save(&self, file_name: &str, mime: &str, contents: &[u8]) -> Result<(), String> {
+        use wasm_bindgen::JsCast as _;
+
+        let js_err = |err: wasm_bindgen::JsValue| format!("Download failed: {err:?}");
+
+        let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(contents));
+        let mut options = web_sys::BlobPropertyBag::new();
+        options.type_(mime);
+        let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options)
+            .map_err(js_err)?;
+        let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(js_err)?;
+
+        let document = web_sys::window()
+            .and_then(|window| window.document())
+            .ok_or("Download failed: no document")?;
+        let anchor: web_sys::HtmlAnchorElement = document
+            .create_element("a")
+            .map_err(js_err)?
+            .dyn_into()
+            .map_err(|_| "Download failed: could not create link")?;
+        anchor.set_href(&url);
+        anchor.set_download(file_name);
+        anchor.click();
+
+        web_sys::Url::revoke_object_url(&url).map_err(js_err)
+    }
+}
@@ -0,0 +1,129 @@
+use egui::Ui;
+
+use crate::{anim::EditorState, model::Play};
+
+/// Older edits are forgotten when the history grows beyond this
+const MAX_ENTRIES: usize = 100;
+
+struct Entry {
+    label: String,
+    play: Play,
+    /// Where the user was after the edit, to go back to with the play
+    editor: EditorState,
+}
+
+/// Undo history of the open play, storing a snapshot after every edit
+#[derive(Default)]
+pub struct History {
+    entries: Vec<Entry>,
+    /// Index of the entry matching the current state
+    current: usize,
+}
+
+impl History {
+    /// Forgets all edits, and starts over from `play`
+    pub fn reset(&mut self, play: &Play, editor: EditorState) {
+        self.entries = vec![Entry {
+            label: "Open play".to_owned(),
+            play: play.clone(),
+            editor,
+        }];
+        self.current = 0;
+    }
+
+    /// Records the state after an edit. Edits that did not change anything
+    /// are ignored, and false is returned.
+    pub fn record(&mut self, label: String, play: &Play, editor: EditorState) -> bool {
+        // Selecting another step or person is not an edit
+        if let Some(entry) = self.entries.get(self.current) {
+            if entry.play == *play {
+                return false;
+            }
+        }
+
+        self.entries.truncate(self.current + 1);
+        self.entries.push(Entry {
+            label,
+            play: play.clone(),
+            editor,
+        });
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+        self.current = self.entries.len() - 1;
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.current > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.current + 1 < self.entries.len()
+    }
+
+    /// Returns the state to restore
+    pub fn undo(&mut self) -> Option<(Play, EditorState)> {
+        self.can_undo().then(|| self.jump(self.current - 1))
+    }
+
+    /// Returns the state to restore
+    pub fn redo(&mut self) -> Option<(Play, EditorState)> {
+        self.can_redo().then(|| self.jump(self.current + 1))
+    }
+
+    fn jump(&mut self, idx: usize) -> (Play, EditorState) {
+        self.current = idx;
+        let entry = &self.entries[idx];
+        (entry.play.clone(), entry.editor)
+    }
+
+    /// Lists all edits. Returns the state to restore if the user clicks one.
+    pub fn display(&mut self, ui: &mut Ui) -> Option<(Play, EditorState)> {
+        let mut restore = None;
+
+        ui.heading("History");
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(self.can_undo(), egui::Button::new("Undo"))
+                .on_hover_text("Ctrl+Z")
+                .clicked()
+            {
+                restore = self.undo();
+            }
+            if ui
+                .add_enabled(self.can_redo(), egui::Button::new("Redo"))
+                .on_hover_text("Ctrl+Shift+Z")
+                .clicked()
+            {
+                restore = self.redo();
+            }
+        });
+
+        ui.separator();
+
+        egui::ScrollArea::vertical()
+            .id_source("history list")
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                let mut clicked = None;
+                for (idx, entry) in self.entries.iter().enumerate() {
+                    let label = egui::RichText::new(&entry.label);
+                    // Edits that have been undone are shown weakly
+                    let label = if idx > self.current {
+                        label.weak()
+                    } else {
+                        label
+                    };
+                    if ui.selectable_label(idx == self.current, label).clicked() {
+                        clicked = Some(idx);
+                    }
+                }
+                if let Some(idx) = clicked {
+                    restore = Some(self.jump(idx));
+                }
+            });
+
+        restore
+    }
+}
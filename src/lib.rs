@@ -1,5 +1,19 @@
 #![warn(clippy::all, rust_2018_idioms)]
 
-mod anim;
+#[cfg(feature = "gui")]
+pub mod anim;
+#[cfg(feature = "gui")]
 mod app;
+pub mod export;
+#[cfg(feature = "gui")]
+mod file_io;
+#[cfg(feature = "gui")]
+mod history;
+pub mod library;
+pub mod model;
+pub mod play_file;
+#[cfg(feature = "gui")]
+mod playback;
+pub mod share;
+#[cfg(feature = "gui")]
 pub use app::JuggeApp;
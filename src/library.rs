@@ -0,0 +1,382 @@
+#[cfg(feature = "gui")]
+use egui::Ui;
+use serde_json::{json, Value};
+
+use crate::model::{save, Play};
+
+/// A named play in the library, together with its metadata.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PlayEntry {
+    pub name: String,
+    pub author: String,
+    /// Seconds since the unix epoch
+    pub created: u64,
+    /// Seconds since the unix epoch
+    pub modified: u64,
+    pub tags: Vec<String>,
+    pub description: String,
+    #[serde(rename = "animation")]
+    pub play: Play,
+}
+
+impl PlayEntry {
+    pub fn new(name: &str, author: &str, play: Play) -> Self {
+        let now = now();
+        Self {
+            name: name.to_owned(),
+            author: author.to_owned(),
+            created: now,
+            modified: now,
+            tags: Vec::new(),
+            description: String::new(),
+            play,
+        }
+    }
+
+    /// Mark the play as modified
+    pub fn touch(&mut self) {
+        self.modified = now();
+    }
+}
+
+/// All plays the user has made. Always contains at least one play.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct Library {
+    pub plays: Vec<PlayEntry>,
+    /// Index of the play open in the editor
+    pub current: usize,
+
+    /// Tag being typed into the metadata
+    #[cfg(feature = "gui")]
+    #[serde(skip)]
+    new_tag: String,
+}
+
+impl Default for Library {
+    fn default() -> Self {
+        Self::from_play(Play::default())
+    }
+}
+
+#[derive(serde::Serialize)]
+struct SavedRef<'a> {
+    version: u32,
+    library: &'a Library,
+}
+
+#[derive(serde::Deserialize)]
+struct Saved {
+    version: u32,
+    library: Value,
+}
+
+/// Opens a play of a library or file saved with schema `version`
+pub(crate) fn play_from_value(
+    mut value: Value,
+    version: u32,
+) -> Result<PlayEntry, save::LoadError> {
+    let Some(entry) = value.as_object_mut() else {
+        return Err(save::LoadError::Invalid("not a play".to_owned()));
+    };
+    let animation = entry.remove("animation").unwrap_or_default();
+    entry.insert("animation".to_owned(), save::migrate(animation, version)?);
+    let play: PlayEntry = serde_json::from_value(value)?;
+    save::check(&play.play)?;
+    Ok(play)
+}
+
+impl Library {
+    pub fn from_play(play: Play) -> Self {
+        Self {
+            plays: vec![PlayEntry::new("Untitled play", "", play)],
+            current: 0,
+            #[cfg(feature = "gui")]
+            new_tag: String::new(),
+        }
+    }
+
+    pub fn current(&self) -> &PlayEntry {
+        &self.plays[self.current]
+    }
+
+    pub fn current_mut(&mut self) -> &mut PlayEntry {
+        &mut self.plays[self.current]
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&SavedRef {
+            version: save::SCHEMA_VERSION,
+            library: self,
+        })
+        .expect("library should always be serializable")
+    }
+
+    /// Opens the plays of the library one at a time. Plays that cannot be opened
+    /// are left out, and why is returned with the library.
+    pub fn from_json(json: &str) -> Result<(Self, Vec<String>), save::LoadError> {
+        let mut saved: Saved = serde_json::from_str(json)?;
+        if !saved.library.is_object() {
+            return Err(save::LoadError::Invalid("not a library".to_owned()));
+        }
+        let plays = match saved.library["plays"].take() {
+            Value::Array(plays) => plays,
+            _ => Vec::new(),
+        };
+        saved.library["plays"] = json!([]);
+        let mut library: Self = serde_json::from_value(saved.library)?;
+
+        let mut skipped = Vec::new();
+        for (idx, value) in plays.into_iter().enumerate() {
+            let name = match value["name"].as_str() {
+                Some(name) => format!("\"{name}\""),
+                None => format!("play {}", idx + 1),
+            };
+            match play_from_value(value, saved.version) {
+                Ok(play) => library.plays.push(play),
+                Err(err) => skipped.push(format!("{name}: {err}")),
+            }
+        }
+        if library.plays.is_empty() {
+            let msg = if skipped.is_empty() {
+                "library has no plays".to_owned()
+            } else {
+                format!("none of the plays could be opened: {}", skipped.join("; "))
+            };
+            return Err(save::LoadError::Invalid(msg));
+        }
+        library.current = library.current.min(library.plays.len() - 1);
+        Ok((library, skipped))
+    }
+
+    pub fn new_play(&mut self) {
+        let author = self.current().author.clone();
+        self.plays
+            .push(PlayEntry::new("Untitled play", &author, Play::default()));
+        self.current = self.plays.len() - 1;
+    }
+
+    /// Adds a play to the library and opens it
+    pub fn add(&mut self, play: PlayEntry) {
+        self.plays.push(play);
+        self.current = self.plays.len() - 1;
+    }
+
+    pub fn duplicate(&mut self, idx: usize) {
+        let mut play = self.plays[idx].clone();
+        play.name = format!("{} (copy)", play.name);
+        play.created = now();
+        play.modified = play.created;
+        self.plays.insert(idx + 1, play);
+        self.current = idx + 1;
+    }
+
+    /// Adds a copy of play `idx` run to the other side, right after it
+    pub fn mirror(&mut self, idx: usize) {
+        let mut play = self.plays[idx].clone();
+        play.name = format!("{} (mirrored)", play.name);
+        play.play = play.play.mirrored();
+        play.created = now();
+        play.modified = play.created;
+        self.plays.insert(idx + 1, play);
+        self.current = idx + 1;
+    }
+
+    pub fn delete(&mut self, idx: usize) {
+        if self.plays.len() == 1 {
+            return;
+        }
+        self.plays.remove(idx);
+        if self.current > idx || self.current == self.plays.len() {
+            self.current -= 1;
+        }
+    }
+
+    /// Lists the plays, and shows the metadata of the current play.
+    /// Returns true if another play was opened.
+    #[cfg(feature = "gui")]
+    pub fn display(&mut self, ui: &mut Ui) -> bool {
+        let mut opened = false;
+
+        ui.heading("Plays");
+        ui.horizontal(|ui| {
+            if ui.button("New").clicked() {
+                self.new_play();
+                opened = true;
+            }
+            if ui.button("Duplicate").clicked() {
+                self.duplicate(self.current);
+                opened = true;
+            }
+            if ui
+                .button("Mirror")
+                .on_hover_text("Copy the play, run to the other side")
+                .clicked()
+            {
+                self.mirror(self.current);
+                opened = true;
+            }
+            let can_delete = self.plays.len() > 1;
+            if ui
+                .add_enabled(can_delete, egui::Button::new("Delete"))
+                .clicked()
+            {
+                self.delete(self.current);
+                opened = true;
+            }
+        });
+
+        ui.separator();
+
+        egui::ScrollArea::vertical()
+            .id_source("play list")
+            .max_height(ui.available_height() / 2.0)
+            .show(ui, |ui| {
+                for (idx, play) in self.plays.iter().enumerate() {
+                    let label = if play.tags.is_empty() {
+                        play.name.clone()
+                    } else {
+                        format!("{} [{}]", play.name, play.tags.join(", "))
+                    };
+                    if ui.selectable_label(idx == self.current, label).clicked()
+                        && idx != self.current
+                    {
+                        self.current = idx;
+                        opened = true;
+                    }
+                }
+            });
+
+        ui.separator();
+
+        self.display_metadata(ui);
+
+        opened
+    }
+
+    #[cfg(feature = "gui")]
+    fn display_metadata(&mut self, ui: &mut Ui) {
+        let play = &mut self.plays[self.current];
+        let mut changed = false;
+
+        egui::Grid::new("play metadata")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Name");
+                changed |= ui.text_edit_singleline(&mut play.name).changed();
+                ui.end_row();
+
+                ui.label("Author");
+                changed |= ui.text_edit_singleline(&mut play.author).changed();
+                ui.end_row();
+
+                ui.label("Created");
+                ui.label(format_timestamp(play.created));
+                ui.end_row();
+
+                ui.label("Modified");
+                ui.label(format_timestamp(play.modified));
+                ui.end_row();
+            });
+
+        ui.label("Tags");
+        ui.horizontal_wrapped(|ui| {
+            let mut remove = None;
+            for (idx, tag) in play.tags.iter().enumerate() {
+                if ui
+                    .button(format!("{tag} ×"))
+                    .on_hover_text("Remove tag")
+                    .clicked()
+                {
+                    remove = Some(idx);
+                }
+            }
+            if let Some(idx) = remove {
+                play.tags.remove(idx);
+                changed = true;
+            }
+
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.new_tag)
+                    .hint_text("Add tag")
+                    .desired_width(80.0),
+            );
+            let tag = self.new_tag.trim();
+            if response.lost_focus() && !tag.is_empty() {
+                if !play.tags.iter().any(|t| t == tag) {
+                    play.tags.push(tag.to_owned());
+                    changed = true;
+                }
+                self.new_tag.clear();
+            }
+        });
+
+        ui.label("Description");
+        changed |= ui
+            .add(egui::TextEdit::multiline(&mut play.description).desired_rows(4))
+            .changed();
+
+        if changed {
+            play.touch();
+        }
+    }
+}
+
+/// Current time in seconds since the unix epoch
+fn now() -> u64 {
+    web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Formats seconds since the unix epoch as `YYYY-MM-DD HH:MM` (UTC)
+#[cfg(feature = "gui")]
+fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let minutes = secs % 86400 / 60;
+
+    // Convert days since epoch to a civil date, see
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}",
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_plays_are_left_out_one_at_a_time() {
+        let mut library = Library::default();
+        library.new_play();
+        library.plays[1].name = "Broken".to_owned();
+        library.plays[1].play.steps[0].duration = 0.0;
+
+        let (opened, skipped) = Library::from_json(&library.to_json()).unwrap();
+
+        assert_eq!(opened.plays.len(), 1);
+        assert_eq!(opened.plays[0].name, library.plays[0].name);
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].starts_with("\"Broken\""));
+        assert_eq!(opened.current, 0);
+    }
+
+    #[test]
+    fn a_library_without_valid_plays_is_rejected() {
+        let mut library = Library::default();
+        library.plays[0].play.steps.clear();
+        assert!(Library::from_json(&library.to_json()).is_err());
+    }
+}
@@ -15,7 +15,7 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "eframe template",
         native_options,
-        Box::new(|cc| Box::new(jugge::JuggeApp::new(cc))),
+        Box::new(|cc| Box::new(jugge::JuggeApp::new(cc, None))),
     )
 }
 
@@ -27,12 +27,21 @@ fn main() {
 
     let web_options = eframe::WebOptions::default();
 
+    // Open the play in links like `https://iverks.github.io/jugge/#play=...`
+    let hash = web_sys::window()
+        .and_then(|window| window.location().hash().ok())
+        .unwrap_or_default();
+    let shared_play = jugge::share::from_hash(&hash).unwrap_or_else(|err| {
+        log::warn!("Ignoring malformed play link: {err}");
+        None
+    });
+
     wasm_bindgen_futures::spawn_local(async {
         eframe::WebRunner::new()
             .start(
                 "the_canvas_id", // hardcode it
                 web_options,
-                Box::new(|cc| Box::new(jugge::JuggeApp::new(cc))),
+                Box::new(|cc| Box::new(jugge::JuggeApp::new(cc, shared_play))),
             )
             .await
             .expect("failed to start eframe");
@@ -0,0 +1,327 @@
+//! Players, referees, the ball and training equipment on the court.
+
+use ecolor::Color32;
+
+use super::{path::Path, spline::Spline, Point};
+
+/// What kind of entity an [`Actor`] is. Despite the name, this includes the
+/// ball, referees and training equipment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum PlayerType {
+    Attacking,
+    Defending,
+    AttackingGoalkeeper,
+    #[serde(alias = "Goalkeeper")]
+    DefendingGoalkeeper,
+    Referee,
+    Ball,
+    Cone,
+    Pole,
+    Mat,
+    Rebounder,
+}
+
+impl PlayerType {
+    /// Players, referees and the ball
+    pub const PEOPLE: [PlayerType; 6] = [
+        PlayerType::Attacking,
+        PlayerType::Defending,
+        PlayerType::AttackingGoalkeeper,
+        PlayerType::DefendingGoalkeeper,
+        PlayerType::Referee,
+        PlayerType::Ball,
+    ];
+
+    pub const EQUIPMENT: [PlayerType; 4] = [
+        PlayerType::Cone,
+        PlayerType::Pole,
+        PlayerType::Mat,
+        PlayerType::Rebounder,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlayerType::Attacking => "attacker",
+            PlayerType::Defending => "defender",
+            PlayerType::AttackingGoalkeeper => "attacking goalkeeper",
+            PlayerType::DefendingGoalkeeper => "defending goalkeeper",
+            PlayerType::Referee => "referee",
+            PlayerType::Ball => "ball",
+            PlayerType::Cone => "cone",
+            PlayerType::Pole => "pole",
+            PlayerType::Mat => "mat",
+            PlayerType::Rebounder => "rebounder",
+        }
+    }
+
+    /// Whether it can move along a path during a step. Equipment stays where it
+    /// is placed, and is in the same place in every step.
+    pub fn can_move(&self) -> bool {
+        !Self::EQUIPMENT.contains(self)
+    }
+
+    /// Whether it can have the ball and pass it. Only players can.
+    pub fn can_hold_ball(&self) -> bool {
+        self.can_move() && !matches!(self, PlayerType::Ball | PlayerType::Referee)
+    }
+
+    /// What it is drawn with, in the editor and in exported pictures
+    pub fn color(&self) -> Color32 {
+        match self {
+            PlayerType::Attacking => Color32::RED,
+            PlayerType::Defending => Color32::BLUE,
+            PlayerType::AttackingGoalkeeper => Color32::from_rgb(230, 120, 0),
+            PlayerType::DefendingGoalkeeper => Color32::DARK_GREEN,
+            PlayerType::Referee => Color32::from_gray(30),
+            PlayerType::Ball => Color32::YELLOW,
+            PlayerType::Cone => Color32::from_rgb(255, 140, 0),
+            PlayerType::Pole => Color32::from_rgb(200, 40, 160),
+            PlayerType::Mat => Color32::from_rgb(70, 110, 160),
+            PlayerType::Rebounder => Color32::GRAY,
+        }
+    }
+
+    /// The shape it is drawn as
+    pub fn glyph(&self) -> Glyph {
+        match self {
+            PlayerType::Attacking | PlayerType::Defending | PlayerType::Ball => Glyph::Circle,
+            PlayerType::AttackingGoalkeeper | PlayerType::DefendingGoalkeeper => Glyph::Square,
+            PlayerType::Referee => Glyph::Striped,
+            PlayerType::Cone => Glyph::Triangle,
+            PlayerType::Pole => Glyph::Bar,
+            PlayerType::Mat => Glyph::Mat,
+            PlayerType::Rebounder => Glyph::Frame,
+        }
+    }
+}
+
+/// The shape a [`PlayerType`] is drawn as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Glyph {
+    Circle,
+    /// Rounded square, for goalkeepers
+    Square,
+    /// Circle with a white ring, for referees
+    Striped,
+    Triangle,
+    /// Tall thin bar
+    Bar,
+    /// Wide flat rectangle
+    Mat,
+    /// Frame with a net across it
+    Frame,
+}
+
+/// How quickly an actor covers the path over the time it moves
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Easing {
+    /// Constant speed
+    #[default]
+    Linear,
+    /// Starts and stops smoothly
+    EaseInOut,
+    /// Accelerates, then keeps top speed through the end
+    Sprint,
+}
+
+/// Fraction of the time a sprint spends accelerating
+const SPRINT_ACCELERATION: f32 = 0.4;
+
+impl Easing {
+    pub const ALL: [Easing; 3] = [Easing::Linear, Easing::EaseInOut, Easing::Sprint];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Easing::Linear => "Constant speed",
+            Easing::EaseInOut => "Ease in and out",
+            Easing::Sprint => "Accelerate, then sprint",
+        }
+    }
+
+    /// Fraction of the path covered after a fraction `u` of the time
+    pub fn distance(&self, u: f32) -> f32 {
+        let u = u.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => u,
+            Easing::EaseInOut => u * u * (3.0 - 2.0 * u),
+            Easing::Sprint => {
+                let a = SPRINT_ACCELERATION;
+                let top = self.top_speed();
+                if u < a {
+                    top * u * u / (2.0 * a)
+                } else {
+                    top * (u - a / 2.0)
+                }
+            }
+        }
+    }
+
+    /// Speed after a fraction `u` of the time, relative to the average speed
+    pub fn speed(&self, u: f32) -> f32 {
+        let u = u.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => 1.0,
+            Easing::EaseInOut => 6.0 * u * (1.0 - u),
+            Easing::Sprint => self.top_speed() * (u / SPRINT_ACCELERATION).min(1.0),
+        }
+    }
+
+    /// Highest speed, relative to the average speed
+    pub fn top_speed(&self) -> f32 {
+        match self {
+            Easing::Linear => 1.0,
+            Easing::EaseInOut => 1.5,
+            Easing::Sprint => 1.0 / (1.0 - SPRINT_ACCELERATION / 2.0),
+        }
+    }
+}
+
+/// When during a step the actor moves, as fractions of the step duration.
+/// The actor waits at the start before `start`, and at the end after `end`.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Timing {
+    pub start: f32,
+    pub end: f32,
+    #[serde(default)]
+    pub easing: Easing,
+}
+
+impl Default for Timing {
+    fn default() -> Self {
+        Self {
+            start: 0.0,
+            end: 1.0,
+            easing: Easing::default(),
+        }
+    }
+}
+
+impl Timing {
+    /// Fraction of the time spent moving that has passed, `frac` into the step
+    fn moving_frac(&self, frac: f32) -> f32 {
+        if self.end <= self.start {
+            return if frac < self.start { 0.0 } else { 1.0 };
+        }
+        ((frac - self.start) / (self.end - self.start)).clamp(0.0, 1.0)
+    }
+
+    /// Fraction of the path the actor has covered, `frac` into the step
+    pub fn progress(&self, frac: f32) -> f32 {
+        self.easing.distance(self.moving_frac(frac))
+    }
+}
+
+/// A player, referee, ball or piece of equipment during one step
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Actor {
+    #[serde(rename = "movement")]
+    pub path: Path,
+    /// Short text shown on the actor, like the position
+    pub label: String,
+    pub p_type: PlayerType,
+    /// Jersey number
+    #[serde(default)]
+    pub number: Option<u32>,
+    #[serde(default)]
+    pub player_name: String,
+    /// Set when the user has moved the start of the actor away from where
+    /// it ended in the previous step, so that linked steps leave it alone
+    #[serde(default)]
+    pub unlinked: bool,
+    #[serde(default)]
+    pub timing: Timing,
+    /// Keep the path starting in the direction the path of the previous step ends in,
+    /// so that the actor does not turn instantly between steps
+    #[serde(default)]
+    pub smooth: bool,
+}
+
+impl Actor {
+    pub fn new(path: Path, label: &str, p_type: PlayerType) -> Self {
+        Self {
+            path,
+            label: label.to_string(),
+            p_type,
+            number: None,
+            player_name: String::new(),
+            unlinked: false,
+            timing: Timing::default(),
+            smooth: false,
+        }
+    }
+
+    pub fn get_color(&self) -> Color32 {
+        self.p_type.color()
+    }
+
+    pub fn still(pt: Point, label: &str, p_type: PlayerType) -> Self {
+        let path = Path::None(pt);
+        Self::new(path, label, p_type)
+    }
+
+    pub fn moving(pts: [Point; 4], label: &str, p_type: PlayerType) -> Self {
+        let path = Path::Spline(Spline::new(pts));
+        Self::new(path, label, p_type)
+    }
+
+    /// The same actor in the next step, continuing the curve `prev` moved along
+    pub fn from_prev(prev: Self) -> Self {
+        let path = match &prev.path {
+            Path::Spline(spline) => {
+                let pts = spline.segment(spline.num_segments() - 1);
+                let prev_move = pts[3] - pts[0];
+                let prev_speed = pts[3] - pts[2];
+                let prev_last_speed = pts[3] - pts[1];
+                let pts = [
+                    pts[3],
+                    pts[3] + prev_speed,
+                    pts[3] + prev_last_speed,
+                    pts[3] + prev_move,
+                ];
+                Path::Spline(Spline::new(pts))
+            }
+            Path::None(pt) => Path::None(*pt),
+        };
+        Self {
+            path,
+            unlinked: false,
+            timing: Timing::default(),
+            ..prev
+        }
+    }
+
+    /// Average speed while moving, in m/s, in a step lasting `duration` seconds.
+    /// Infinite if the actor moves without taking any time.
+    pub fn average_speed(&self, duration: f32) -> f32 {
+        let length = self.path.length();
+        if length == 0.0 {
+            return 0.0;
+        }
+        length / ((self.timing.end - self.timing.start) * duration)
+    }
+
+    /// Speed in m/s `frac` into a step lasting `duration` seconds
+    pub fn speed_at(&self, frac: f32, duration: f32) -> f32 {
+        if frac < self.timing.start || frac > self.timing.end {
+            return 0.0;
+        }
+        let u = self.timing.moving_frac(frac);
+        self.average_speed(duration) * self.timing.easing.speed(u)
+    }
+
+    /// The label or player name, or what kind of entity this is if it has neither
+    pub fn name(&self) -> &str {
+        if !self.label.is_empty() {
+            return &self.label;
+        }
+        if !self.player_name.is_empty() {
+            return &self.player_name;
+        }
+        self.p_type.name()
+    }
+
+    /// Where the actor is `frac` into the step
+    pub fn position_at(&self, frac: f32) -> Point {
+        self.path.at(self.timing.progress(frac))
+    }
+}
@@ -0,0 +1,358 @@
+//! Who has the ball, and how it travels between players.
+
+use emath::Vec2;
+
+use super::{
+    field::{GOAL_HEIGHT, GOAL_WIDTH, LENGTH, WIDTH},
+    PlayerType, Point, Step,
+};
+
+/// Where the ball is held, relative to the carrier, in meters
+const BALL_OFFSET: Vec2 = Vec2::new(0.5, -0.5);
+/// Highest point of a lob, in meters
+const LOB_HEIGHT: f32 = 3.0;
+/// Height the ball is thrown and caught at, in meters
+const HAND_HEIGHT: f32 = 1.2;
+/// How far along a bounce pass the ball hits the floor
+const BOUNCE_AT: f32 = 0.6;
+/// How fast a shot flies, in meters per second
+const SHOT_SPEED: f32 = 20.0;
+/// How far inside the posts, crossbar and floor the corners of the goal are aimed at
+const CORNER_MARGIN: f32 = 0.3;
+
+/// How the ball travels through the air
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Flight {
+    #[default]
+    Straight,
+    Bounce,
+    Lob,
+}
+
+impl Flight {
+    pub const ALL: [Flight; 3] = [Flight::Straight, Flight::Bounce, Flight::Lob];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Flight::Straight => "straight",
+            Flight::Bounce => "bounce",
+            Flight::Lob => "lob",
+        }
+    }
+
+    /// Height of the ball above the floor in meters, a fraction `u` into the flight
+    pub fn height(&self, u: f32) -> f32 {
+        let u = u.clamp(0.0, 1.0);
+        match self {
+            Flight::Straight => HAND_HEIGHT,
+            Flight::Bounce if u < BOUNCE_AT => HAND_HEIGHT * (1.0 - u / BOUNCE_AT),
+            Flight::Bounce => HAND_HEIGHT * (u - BOUNCE_AT) / (1.0 - BOUNCE_AT),
+            Flight::Lob => HAND_HEIGHT + 4.0 * (LOB_HEIGHT - HAND_HEIGHT) * u * (1.0 - u),
+        }
+    }
+}
+
+/// Where in the goal a shot is aimed, as seen by the shooter
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum GoalTarget {
+    UpperLeft,
+    UpperMiddle,
+    UpperRight,
+    MiddleLeft,
+    #[default]
+    Middle,
+    MiddleRight,
+    LowLeft,
+    LowMiddle,
+    LowRight,
+}
+
+impl GoalTarget {
+    pub const ALL: [GoalTarget; 9] = [
+        GoalTarget::UpperLeft,
+        GoalTarget::UpperMiddle,
+        GoalTarget::UpperRight,
+        GoalTarget::MiddleLeft,
+        GoalTarget::Middle,
+        GoalTarget::MiddleRight,
+        GoalTarget::LowLeft,
+        GoalTarget::LowMiddle,
+        GoalTarget::LowRight,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GoalTarget::UpperLeft => "upper left",
+            GoalTarget::UpperMiddle => "upper middle",
+            GoalTarget::UpperRight => "upper right",
+            GoalTarget::MiddleLeft => "middle left",
+            GoalTarget::Middle => "middle",
+            GoalTarget::MiddleRight => "middle right",
+            GoalTarget::LowLeft => "low left",
+            GoalTarget::LowMiddle => "low middle",
+            GoalTarget::LowRight => "low right",
+        }
+    }
+
+    /// Meters to the right of the middle of the goal, as seen by the shooter
+    pub fn across(&self) -> f32 {
+        let side = GOAL_WIDTH / 2.0 - CORNER_MARGIN;
+        match self {
+            GoalTarget::UpperLeft | GoalTarget::MiddleLeft | GoalTarget::LowLeft => -side,
+            GoalTarget::UpperMiddle | GoalTarget::Middle | GoalTarget::LowMiddle => 0.0,
+            GoalTarget::UpperRight | GoalTarget::MiddleRight | GoalTarget::LowRight => side,
+        }
+    }
+
+    /// The same height on the other side of the goal
+    pub fn mirrored(&self) -> GoalTarget {
+        match self {
+            GoalTarget::UpperLeft => GoalTarget::UpperRight,
+            GoalTarget::UpperRight => GoalTarget::UpperLeft,
+            GoalTarget::MiddleLeft => GoalTarget::MiddleRight,
+            GoalTarget::MiddleRight => GoalTarget::MiddleLeft,
+            GoalTarget::LowLeft => GoalTarget::LowRight,
+            GoalTarget::LowRight => GoalTarget::LowLeft,
+            middle => *middle,
+        }
+    }
+
+    /// Meters above the floor
+    pub fn height(&self) -> f32 {
+        match self {
+            GoalTarget::UpperLeft | GoalTarget::UpperMiddle | GoalTarget::UpperRight => {
+                GOAL_HEIGHT - CORNER_MARGIN
+            }
+            GoalTarget::MiddleLeft | GoalTarget::Middle | GoalTarget::MiddleRight => {
+                GOAL_HEIGHT / 2.0
+            }
+            GoalTarget::LowLeft | GoalTarget::LowMiddle | GoalTarget::LowRight => CORNER_MARGIN,
+        }
+    }
+}
+
+/// The player that has the ball after the passes of a step shoots at the goal
+/// closest to them. The time is a fraction of the step duration.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Shot {
+    pub time: f32,
+    pub target: GoalTarget,
+    /// Where the goalkeeper goes to save the shot
+    #[serde(default)]
+    pub reaction: Option<GoalTarget>,
+}
+
+/// The goal closest to `pt`, as a point on the goal line and the direction
+/// to the right of a shooter facing it
+fn goal_facing(pt: Point) -> (Point, Vec2) {
+    if pt.y <= LENGTH / 2.0 {
+        (Point::new(WIDTH / 2.0, 0.0), Vec2::new(1.0, 0.0))
+    } else {
+        (Point::new(WIDTH / 2.0, LENGTH), Vec2::new(-1.0, 0.0))
+    }
+}
+
+/// Where `target` is on the goal closest to a shooter standing at `pt`
+pub fn goal_point(pt: Point, target: GoalTarget) -> Point {
+    let (middle, right) = goal_facing(pt);
+    middle + right * target.across()
+}
+
+/// The goal posts closest to a shooter standing at `pt`
+pub fn goal_posts(pt: Point) -> [Point; 2] {
+    let (middle, right) = goal_facing(pt);
+    [
+        middle - right * GOAL_WIDTH / 2.0,
+        middle + right * GOAL_WIDTH / 2.0,
+    ]
+}
+
+/// The ball is thrown from one player to another during a step.
+/// Times are fractions of the step duration.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Pass {
+    pub from: usize,
+    pub to: usize,
+    pub throw: f32,
+    pub catch: f32,
+    #[serde(default)]
+    pub flight: Flight,
+}
+
+impl Step {
+    /// Index of the ball, if there is one
+    pub fn ball(&self) -> Option<usize> {
+        self.actors
+            .iter()
+            .position(|actor| actor.p_type == PlayerType::Ball)
+    }
+
+    /// Who has the ball after the passes of the step
+    pub fn holder(&self) -> Option<usize> {
+        self.passes.last().map(|pass| pass.to).or(self.carrier)
+    }
+
+    /// Who has the ball at the end of the step. Nobody does after a shot.
+    pub fn final_carrier(&self) -> Option<usize> {
+        self.holder().filter(|_| self.shot.is_none())
+    }
+
+    /// Where the shooter releases the ball, where it hits the goal and how high,
+    /// and when the ball gets there as a fraction of the step
+    pub fn shot_flight(&self) -> Option<(Point, Point, f32, f32)> {
+        let shot = self.shot.as_ref()?;
+        let from = self.actors[self.holder()?].position_at(shot.time) + BALL_OFFSET;
+        let to = goal_point(from, shot.target);
+        let flight_time = from.distance(to) / SHOT_SPEED / self.duration;
+        let arrival = (shot.time + flight_time).min(1.0);
+        Some((from, to, shot.target.height(), arrival))
+    }
+
+    /// Where the ball is `frac` into the step, and how high above the floor.
+    /// After a shot it stays in the goal.
+    /// `None` if nobody has the ball, so it is wherever the user put it.
+    pub fn ball_at(&self, frac: f32) -> Option<(Point, f32)> {
+        let mut carrier = self.carrier?;
+        for pass in &self.passes {
+            if frac < pass.throw {
+                break;
+            }
+            if frac < pass.catch {
+                let from = self.actors[pass.from].position_at(pass.throw) + BALL_OFFSET;
+                let to = self.actors[pass.to].position_at(pass.catch) + BALL_OFFSET;
+                let u = (frac - pass.throw) / (pass.catch - pass.throw);
+                return Some((from.lerp(to, u), pass.flight.height(u)));
+            }
+            carrier = pass.to;
+        }
+        if let (Some(shot), Some((from, to, height, arrival))) = (&self.shot, self.shot_flight()) {
+            if frac >= shot.time {
+                let u = if arrival > shot.time {
+                    ((frac - shot.time) / (arrival - shot.time)).min(1.0)
+                } else {
+                    1.0
+                };
+                let height = HAND_HEIGHT + (height - HAND_HEIGHT) * u;
+                return Some((from.lerp(to, u), height));
+            }
+        }
+        let pt = self.actors[carrier].position_at(frac) + BALL_OFFSET;
+        Some((pt, HAND_HEIGHT))
+    }
+
+    /// Where everyone is `frac` into the step, with the ball following whoever has it
+    pub fn positions(&self, frac: f32) -> Vec<Point> {
+        let mut positions: Vec<Point> = self
+            .actors
+            .iter()
+            .map(|actor| actor.position_at(frac))
+            .collect();
+        if let (Some(ball), Some((pt, _))) = (self.ball(), self.ball_at(frac)) {
+            positions[ball] = pt;
+        }
+        positions
+    }
+
+    /// Where the ball is thrown from and caught at for every pass
+    pub fn pass_lines(&self) -> Vec<(Point, Point, Flight)> {
+        self.passes
+            .iter()
+            .map(|pass| {
+                let from = self.actors[pass.from].position_at(pass.throw) + BALL_OFFSET;
+                let to = self.actors[pass.to].position_at(pass.catch) + BALL_OFFSET;
+                (from, to, pass.flight)
+            })
+            .collect()
+    }
+
+    /// Forgets actor `idx`, which is being removed, and its passes.
+    /// Without the ball, nobody can have it.
+    pub(super) fn forget_actor(&mut self, idx: usize) {
+        let shift = |i: usize| if i > idx { i - 1 } else { i };
+        let num_balls = self
+            .actors
+            .iter()
+            .filter(|actor| actor.p_type == PlayerType::Ball)
+            .count();
+        let last_ball = self.actors[idx].p_type == PlayerType::Ball && num_balls == 1;
+        if self.carrier == Some(idx) || last_ball {
+            self.carrier = None;
+            self.passes.clear();
+            self.shot = None;
+        }
+        // Later passes make no sense without the ones before them
+        if let Some(first) = self
+            .passes
+            .iter()
+            .position(|pass| pass.from == idx || pass.to == idx)
+        {
+            self.passes.truncate(first);
+            self.shot = None;
+        }
+        self.carrier = self.carrier.map(shift);
+        for pass in self.passes.iter_mut() {
+            pass.from = shift(pass.from);
+            pass.to = shift(pass.to);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::attack_with_ball;
+
+    /// The first step of a play where the first attacker passes to the second
+    fn pass() -> (Step, usize, usize) {
+        let (mut play, attackers) = attack_with_ball();
+        let mut step = play.steps.remove(0);
+        let (from, to) = (attackers[0], attackers[1]);
+        step.passes.push(Pass {
+            from,
+            to,
+            throw: 0.2,
+            catch: 0.5,
+            flight: Flight::Straight,
+        });
+        (step, from, to)
+    }
+
+    #[test]
+    fn the_ball_follows_whoever_has_it() {
+        let (step, from, to) = pass();
+        let held_by = |idx: usize, frac: f32| step.actors[idx].position_at(frac) + BALL_OFFSET;
+
+        assert_eq!(step.ball_at(0.1), Some((held_by(from, 0.1), HAND_HEIGHT)));
+        assert_eq!(step.ball_at(0.6), Some((held_by(to, 0.6), HAND_HEIGHT)));
+        let (mid_pass, _) = step.ball_at(0.35).unwrap();
+        assert!(mid_pass.distance(held_by(from, 0.2).lerp(held_by(to, 0.5), 0.5)) < 1e-4);
+        assert_eq!(step.final_carrier(), Some(to));
+    }
+
+    /// The same pass, after which the second attacker shoots
+    fn pass_and_shot() -> (Step, usize) {
+        let (mut step, _, to) = pass();
+        step.shot = Some(Shot {
+            time: 0.7,
+            target: GoalTarget::UpperLeft,
+            reaction: None,
+        });
+        (step, to)
+    }
+
+    #[test]
+    fn nobody_has_the_ball_after_a_shot() {
+        let (step, shooter) = pass_and_shot();
+        assert_eq!(step.holder(), Some(shooter));
+        assert_eq!(step.final_carrier(), None);
+    }
+
+    #[test]
+    fn a_shot_ends_in_the_goal() {
+        let (step, _) = pass_and_shot();
+        let (_, to, height, _) = step.shot_flight().unwrap();
+        let (pt, ball_height) = step.ball_at(1.0).unwrap();
+        assert_eq!(pt, to);
+        assert_eq!(ball_height, height);
+    }
+}
@@ -0,0 +1,101 @@
+//! Cubic bezier curves, given by their four control points.
+
+use super::Point;
+
+pub fn bez_at_t(pts: [Point; 4], t: f32) -> Point {
+    (1.0 - t).powi(3) * pts[0]
+        + 3.0 * (1.0 - t).powi(2) * t * pts[1].to_vec2()
+        + 3.0 * (1.0 - t) * t.powi(2) * pts[2].to_vec2()
+        + t.powi(3) * pts[3].to_vec2()
+}
+
+/// Splits the curve at `t` into two curves with the same shape
+pub fn bez_split(pts: [Point; 4], t: f32) -> ([Point; 4], [Point; 4]) {
+    let a = pts[0].lerp(pts[1], t);
+    let b = pts[1].lerp(pts[2], t);
+    let c = pts[2].lerp(pts[3], t);
+    let ab = a.lerp(b, t);
+    let bc = b.lerp(c, t);
+    let mid = ab.lerp(bc, t);
+    ([pts[0], a, ab, mid], [mid, bc, c, pts[3]])
+}
+
+/// Number of straight pieces used to measure the length of a bezier curve
+const ARC_SAMPLES: usize = 32;
+
+/// Length of the curve from the start to evenly spaced values of t
+fn bez_arc_lengths(pts: [Point; 4]) -> [f32; ARC_SAMPLES + 1] {
+    let mut lengths = [0.0; ARC_SAMPLES + 1];
+    let mut prev = pts[0];
+    for i in 1..=ARC_SAMPLES {
+        let pt = bez_at_t(pts, i as f32 / ARC_SAMPLES as f32);
+        lengths[i] = lengths[i - 1] + prev.distance(pt);
+        prev = pt;
+    }
+    lengths
+}
+
+pub fn bez_length(pts: [Point; 4]) -> f32 {
+    bez_arc_lengths(pts)[ARC_SAMPLES]
+}
+
+/// Get the point a fraction `s` of the length along the curve, so that
+/// evenly spaced `s` gives points evenly spaced along the curve
+pub fn bez_at_distance(pts: [Point; 4], s: f32) -> Point {
+    let lengths = bez_arc_lengths(pts);
+    let total = lengths[ARC_SAMPLES];
+    if total <= 0.0 {
+        return pts[0];
+    }
+
+    let target = s.clamp(0.0, 1.0) * total;
+    let idx = lengths
+        .partition_point(|&length| length < target)
+        .clamp(1, ARC_SAMPLES);
+    let (before, after) = (lengths[idx - 1], lengths[idx]);
+    let piece_frac = if after > before {
+        (target - before) / (after - before)
+    } else {
+        0.0
+    };
+    bez_at_t(pts, ((idx - 1) as f32 + piece_frac) / ARC_SAMPLES as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pt(x: f32, y: f32) -> Point {
+        Point::new(x, y)
+    }
+
+    #[test]
+    fn a_split_curve_keeps_the_shape() {
+        let pts = [pt(0.0, 0.0), pt(2.0, 4.0), pt(6.0, 4.0), pt(8.0, 0.0)];
+        let (before, after) = bez_split(pts, 0.25);
+        for i in 0..=10 {
+            let t = i as f32 / 10.0;
+            assert!(bez_at_t(before, t).distance(bez_at_t(pts, 0.25 * t)) < 1e-4);
+            assert!(bez_at_t(after, t).distance(bez_at_t(pts, 0.25 + 0.75 * t)) < 1e-4);
+        }
+    }
+
+    #[test]
+    fn samples_are_spread_evenly_by_length() {
+        // Handles bunched at the start, so that t runs unevenly along the line
+        let pts = [pt(0.0, 0.0), pt(0.1, 0.0), pt(0.2, 0.0), pt(9.0, 0.0)];
+        assert!((bez_length(pts) - 9.0).abs() < 1e-4);
+        for i in 0..=10 {
+            let s = i as f32 / 10.0;
+            let x = bez_at_distance(pts, s).x;
+            assert!((x - 9.0 * s).abs() < 0.1, "{x} is not {}", 9.0 * s);
+        }
+    }
+
+    #[test]
+    fn a_curve_without_length_stays_at_its_start() {
+        let pts = [pt(1.0, 2.0); 4];
+        assert_eq!(bez_length(pts), 0.0);
+        assert_eq!(bez_at_distance(pts, 0.5), pt(1.0, 2.0));
+    }
+}
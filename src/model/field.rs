@@ -0,0 +1,147 @@
+//! A handball court in meters.
+//!
+//! `x` goes across the court, from the left sideline at 0 to the right sideline at
+//! [`WIDTH`]. `y` goes along the court, from the goal line at the top at 0 to the
+//! other goal line at [`LENGTH`], so the center line is at `y = LENGTH / 2`.
+
+use emath::{Rect, Vec2};
+
+use super::Point;
+
+pub const WIDTH: f32 = 20.0;
+pub const LENGTH: f32 = 40.0;
+pub const GOAL_WIDTH: f32 = 3.0;
+/// How far the goal reaches behind the goal line
+pub const GOAL_DEPTH: f32 = 1.0;
+pub const GOAL_HEIGHT: f32 = 2.0;
+const GOAL_AREA: f32 = 6.0;
+const FREE_THROW: f32 = 9.0;
+const PENALTY: f32 = 7.0;
+/// Length of the 7m line
+const PENALTY_LINE: f32 = 1.0;
+const RESTRAINING: f32 = 4.0;
+/// Length of the 4m goalkeeper restraining line, and how far the marks at the
+/// end of the substitution zones reach on each side of the sideline
+const SHORT_MARK: f32 = 0.15;
+/// Length of the substitution zone along the sideline, from the center line
+const SUBSTITUTION: f32 = 4.5;
+/// Room around the court that is shown, so players on the lines are visible
+const MARGIN: f32 = 1.0;
+
+/// Straight pieces in each arc
+const ARC_PIECES: usize = 24;
+
+/// How much of the court is shown
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum FieldView {
+    /// The half with the goal at the top
+    #[default]
+    Half,
+    Full,
+}
+
+impl FieldView {
+    pub const ALL: [FieldView; 2] = [FieldView::Half, FieldView::Full];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FieldView::Half => "Half court",
+            FieldView::Full => "Full court",
+        }
+    }
+
+    /// The part of the court that is shown, in meters
+    pub fn bounds(&self) -> Rect {
+        let length = match self {
+            FieldView::Half => LENGTH / 2.0,
+            FieldView::Full => LENGTH,
+        };
+        Rect::from_min_max(Point::ZERO, Point::new(WIDTH, length)).expand(MARGIN)
+    }
+}
+
+/// A line on the court, in meters
+#[derive(Debug, Clone, PartialEq)]
+pub enum Marking {
+    Line(Vec<Point>),
+    Dashed(Vec<Point>),
+    /// The goal, from post to post and behind the goal line
+    Goal(Rect),
+}
+
+/// All lines on the part of the court shown in `view`
+pub fn markings(view: FieldView) -> Vec<Marking> {
+    let half = LENGTH / 2.0;
+    let mut markings = vec![Marking::Line(vec![
+        Point::new(0.0, 0.0),
+        Point::new(WIDTH, 0.0),
+        Point::new(WIDTH, half),
+        Point::new(0.0, half),
+        Point::new(0.0, 0.0),
+    ])];
+    markings.extend(half_markings());
+
+    if view == FieldView::Full {
+        markings.push(Marking::Line(vec![
+            Point::new(0.0, half),
+            Point::new(0.0, LENGTH),
+            Point::new(WIDTH, LENGTH),
+            Point::new(WIDTH, half),
+        ]));
+        // The other half is the same, upside down
+        let flip = |pt: Point| Point::new(pt.x, LENGTH - pt.y);
+        markings.extend(half_markings().into_iter().map(|marking| match marking {
+            Marking::Line(pts) => Marking::Line(pts.into_iter().map(flip).collect()),
+            Marking::Dashed(pts) => Marking::Dashed(pts.into_iter().map(flip).collect()),
+            Marking::Goal(rect) => {
+                Marking::Goal(Rect::from_two_pos(flip(rect.min), flip(rect.max)))
+            }
+        }));
+    }
+
+    markings
+}
+
+/// Markings of the half with the goal at the top, except for the outline
+fn half_markings() -> Vec<Marking> {
+    let left_post = Point::new((WIDTH - GOAL_WIDTH) / 2.0, 0.0);
+    let right_post = Point::new((WIDTH + GOAL_WIDTH) / 2.0, 0.0);
+    let center_x = WIDTH / 2.0;
+    let substitution_y = LENGTH / 2.0 - SUBSTITUTION;
+
+    vec![
+        Marking::Goal(Rect::from_min_max(
+            left_post - Vec2::new(0.0, GOAL_DEPTH),
+            right_post,
+        )),
+        Marking::Line(goal_area_line(left_post, right_post, GOAL_AREA)),
+        Marking::Dashed(goal_area_line(left_post, right_post, FREE_THROW)),
+        Marking::Line(vec![
+            Point::new(center_x - PENALTY_LINE / 2.0, PENALTY),
+            Point::new(center_x + PENALTY_LINE / 2.0, PENALTY),
+        ]),
+        Marking::Line(vec![
+            Point::new(center_x - SHORT_MARK / 2.0, RESTRAINING),
+            Point::new(center_x + SHORT_MARK / 2.0, RESTRAINING),
+        ]),
+        // End of the substitution zone, which runs to the center line
+        Marking::Line(vec![
+            Point::new(WIDTH - SHORT_MARK, substitution_y),
+            Point::new(WIDTH + SHORT_MARK, substitution_y),
+        ]),
+    ]
+}
+
+/// Quarter circles of `radius` around each goal post, joined by a straight
+/// line parallel to the goal line, and cut off at the sidelines
+fn goal_area_line(left_post: Point, right_post: Point, radius: f32) -> Vec<Point> {
+    // Both posts are as far from their sideline
+    let max_angle = (left_post.x / radius).min(1.0).asin();
+    let angle = |i: usize| i as f32 / ARC_PIECES as f32 * max_angle;
+    let left_arc = (0..=ARC_PIECES)
+        .rev()
+        .map(|i| left_post + radius * Vec2::new(-angle(i).sin(), angle(i).cos()));
+    let right_arc =
+        (0..=ARC_PIECES).map(|i| right_post + radius * Vec2::new(angle(i).sin(), angle(i).cos()));
+    left_arc.chain(right_arc).collect()
+}
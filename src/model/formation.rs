@@ -0,0 +1,211 @@
+//! Standard ways to line up a team, and ones the user saved.
+
+use super::{Actor, PlayerType, Point, Step};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Team {
+    Attack,
+    Defense,
+}
+
+impl Team {
+    pub const ALL: [Team; 2] = [Team::Attack, Team::Defense];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Team::Attack => "Attack",
+            Team::Defense => "Defense",
+        }
+    }
+
+    /// The players in the team. Goalkeepers are left where they are.
+    pub fn p_type(&self) -> PlayerType {
+        match self {
+            Team::Attack => PlayerType::Attacking,
+            Team::Defense => PlayerType::Defending,
+        }
+    }
+}
+
+/// Where a player stands in a formation, and what they are called
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Spot {
+    pub label: String,
+    pub pt: Point,
+}
+
+/// Where every player of a team stands, facing the goal at the top
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Formation {
+    pub name: String,
+    pub team: Team,
+    pub spots: Vec<Spot>,
+}
+
+impl Formation {
+    fn preset(name: &str, team: Team, spots: &[(&str, f32, f32)]) -> Self {
+        Self {
+            name: name.to_owned(),
+            team,
+            spots: spots
+                .iter()
+                .map(|&(label, x, y)| Spot {
+                    label: label.to_owned(),
+                    pt: Point::new(x, y),
+                })
+                .collect(),
+        }
+    }
+
+    /// The formations that come with the app. Players are listed from left to
+    /// right in the same order in every formation of a team, so that switching
+    /// formation moves each player to the spot of the same role.
+    pub fn presets() -> Vec<Formation> {
+        use Team::{Attack, Defense};
+        vec![
+            Self::preset(
+                "3-3",
+                Attack,
+                &[
+                    ("LW", 0.4, 0.4),
+                    ("LB", 2.2, 11.2),
+                    ("CB", 10.0, 12.4),
+                    ("PV", 10.0, 6.4),
+                    ("RB", 17.8, 11.2),
+                    ("RW", 19.6, 0.4),
+                ],
+            ),
+            Self::preset(
+                "2-4",
+                Attack,
+                &[
+                    ("LW", 0.4, 0.4),
+                    ("LB", 6.0, 11.5),
+                    ("P2", 7.5, 6.5),
+                    ("PV", 12.5, 6.5),
+                    ("RB", 14.0, 11.5),
+                    ("RW", 19.6, 0.4),
+                ],
+            ),
+            Self::preset(
+                "7 vs 6, empty goal",
+                Attack,
+                &[
+                    ("LW", 0.4, 0.4),
+                    ("LB", 3.0, 11.0),
+                    ("CB", 10.0, 12.4),
+                    ("PV", 8.0, 6.5),
+                    ("RB", 17.0, 11.0),
+                    ("RW", 19.6, 0.4),
+                    ("P2", 12.0, 6.5),
+                ],
+            ),
+            Self::preset(
+                "6-0",
+                Defense,
+                &[
+                    ("LW", 3.2, 3.0),
+                    ("LB", 5.6, 5.6),
+                    ("CB", 8.6, 6.4),
+                    ("PV", 11.4, 6.4),
+                    ("RB", 14.4, 5.6),
+                    ("RW", 16.8, 3.0),
+                ],
+            ),
+            Self::preset(
+                "5-1",
+                Defense,
+                &[
+                    ("LW", 3.2, 3.0),
+                    ("LB", 6.0, 6.0),
+                    ("CB", 10.0, 9.5),
+                    ("PV", 10.0, 6.5),
+                    ("RB", 14.0, 6.0),
+                    ("RW", 16.8, 3.0),
+                ],
+            ),
+            Self::preset(
+                "3-2-1",
+                Defense,
+                &[
+                    ("LW", 4.0, 3.5),
+                    ("LB", 6.0, 8.0),
+                    ("CB", 10.0, 10.0),
+                    ("PV", 10.0, 6.3),
+                    ("RB", 14.0, 8.0),
+                    ("RW", 16.0, 3.5),
+                ],
+            ),
+            Self::preset(
+                "4-2",
+                Defense,
+                &[
+                    ("LW", 3.2, 3.0),
+                    ("LB", 6.5, 8.5),
+                    ("CB", 8.3, 6.3),
+                    ("PV", 11.7, 6.3),
+                    ("RB", 13.5, 8.5),
+                    ("RW", 16.8, 3.0),
+                ],
+            ),
+            Self::preset(
+                "3-3",
+                Defense,
+                &[
+                    ("LW", 4.5, 4.0),
+                    ("LB", 5.0, 9.0),
+                    ("CB", 10.0, 9.5),
+                    ("PV", 10.0, 6.3),
+                    ("RB", 15.0, 9.0),
+                    ("RW", 15.5, 4.0),
+                ],
+            ),
+            // Each defender on the goal side of the attacker in the same spot of the 3-3 attack
+            Self::preset(
+                "Man-to-man",
+                Defense,
+                &[
+                    ("LW", 1.5, 1.5),
+                    ("LB", 3.0, 9.8),
+                    ("CB", 10.0, 11.0),
+                    ("PV", 11.2, 6.6),
+                    ("RB", 17.0, 9.8),
+                    ("RW", 18.5, 1.5),
+                ],
+            ),
+        ]
+    }
+
+    /// The preset called `name`
+    pub fn preset_named(team: Team, name: &str) -> Formation {
+        Self::presets()
+            .into_iter()
+            .find(|formation| formation.team == team && formation.name == name)
+            .expect("the preset exists")
+    }
+
+    /// The players of the formation, standing still
+    pub fn actors(&self) -> impl Iterator<Item = Actor> + '_ {
+        let p_type = self.team.p_type();
+        self.spots
+            .iter()
+            .map(move |spot| Actor::still(spot.pt, &spot.label, p_type))
+    }
+
+    /// Saves where the `team` stands at the start of `step`
+    pub fn from_step(name: &str, team: Team, step: &Step) -> Self {
+        Self {
+            name: name.to_owned(),
+            team,
+            spots: step
+                .actors
+                .iter()
+                .filter(|actor| actor.p_type == team.p_type())
+                .map(|actor| Spot {
+                    label: actor.label.clone(),
+                    pt: actor.path.start(),
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Plays run to the other side.
+
+use super::{field::WIDTH, Play};
+
+/// Positions on the left and their counterparts on the right
+const SIDES: [(&str, &str); 2] = [("LW", "RW"), ("LB", "RB")];
+
+/// The label of the same position on the other side, e.g. `RW` for `LW`.
+/// Other labels are kept.
+pub fn mirror_label(label: &str) -> String {
+    for (left, right) in SIDES {
+        if label.eq_ignore_ascii_case(left) {
+            return match_case(label, right);
+        }
+        if label.eq_ignore_ascii_case(right) {
+            return match_case(label, left);
+        }
+    }
+    label.to_owned()
+}
+
+/// `label` written in lower case if `like` is
+fn match_case(like: &str, label: &str) -> String {
+    if like.chars().all(|c| c.is_ascii_lowercase()) {
+        label.to_ascii_lowercase()
+    } else {
+        label.to_owned()
+    }
+}
+
+impl Play {
+    /// The same play run to the other side, mirrored across the long axis of
+    /// the court, with left and right positions swapped
+    pub fn mirrored(&self) -> Play {
+        let mut mirrored = self.clone();
+        for step in mirrored.steps.iter_mut() {
+            for actor in step.actors.iter_mut() {
+                for pt in actor.path.points_mut() {
+                    pt.x = WIDTH - pt.x;
+                }
+                actor.label = mirror_label(&actor.label);
+            }
+            if let Some(shot) = &mut step.shot {
+                shot.target = shot.target.mirrored();
+                shot.reaction = shot.reaction.map(|reaction| reaction.mirrored());
+            }
+        }
+        mirrored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{
+        ball::{GoalTarget, Shot},
+        Actor, PlayerType, Point,
+    };
+
+    #[test]
+    fn positions_on_the_left_and_right_are_swapped() {
+        assert_eq!(mirror_label("LW"), "RW");
+        assert_eq!(mirror_label("rb"), "lb");
+        assert_eq!(mirror_label("CB"), "CB");
+    }
+
+    #[test]
+    fn a_mirrored_play_is_run_to_the_other_side() {
+        let pts = [(2.0, 2.0), (3.0, 4.0), (5.0, 6.0), (6.0, 9.0)].map(|(x, y)| Point::new(x, y));
+        let mut play = Play::new(vec![Actor::moving(pts, "LW", PlayerType::Attacking)]);
+        play.steps[0].shot = Some(Shot {
+            time: 0.5,
+            target: GoalTarget::UpperLeft,
+            reaction: Some(GoalTarget::LowRight),
+        });
+
+        let mirrored = play.mirrored();
+
+        let actor = &mirrored.steps[0].actors[0];
+        let expected = pts.map(|pt| Point::new(WIDTH - pt.x, pt.y));
+        assert_eq!(actor.path.points(), expected);
+        assert_eq!(actor.label, "RW");
+        let shot = mirrored.steps[0].shot.as_ref().unwrap();
+        assert_eq!(shot.target, GoalTarget::UpperRight);
+        assert_eq!(shot.reaction, Some(GoalTarget::LowLeft));
+        assert_eq!(mirrored.mirrored(), play);
+    }
+}
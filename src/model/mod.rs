@@ -0,0 +1,438 @@
+//! Plays, without anything to draw or edit them with.
+//!
+//! A [`Play`] is a list of [`Step`]s, played one after another. In each step,
+//! every [`Actor`] follows its [`Path`] on the court. [`Play::positions_at`]
+//! tells where everyone is at a given time, and [`Step::positions`] where
+//! everyone is a fraction into a step. Coordinates are in meters, see [`field`].
+
+pub mod actor;
+pub mod ball;
+mod bezier;
+pub mod field;
+pub mod formation;
+pub mod mirror;
+pub mod path;
+pub mod save;
+pub mod spline;
+
+use std::ops::RangeInclusive;
+
+use emath::Pos2;
+
+pub use self::{
+    actor::{Actor, Easing, Glyph, PlayerType, Timing},
+    path::Path,
+};
+use self::{
+    ball::{Pass, Shot},
+    field::FieldView,
+    formation::{Formation, Team},
+};
+
+pub type Point = Pos2;
+
+/// Duration of new steps, in seconds
+const DEFAULT_DURATION: f32 = 1.0;
+/// Allowed step durations, in seconds
+pub const DURATION_RANGE: RangeInclusive<f32> = 0.1..=60.0;
+
+/// One step of the play
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Step {
+    /// The same actors, in the same order, in every step
+    #[serde(rename = "persons")]
+    pub actors: Vec<Actor>,
+    /// How long the step lasts, in seconds
+    pub duration: f32,
+    /// Index of the player that has the ball when the step starts
+    #[serde(default)]
+    pub carrier: Option<usize>,
+    /// In the order they are thrown, each from the player that has the ball
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub passes: Vec<Pass>,
+    /// Ends the step with a shot from the player that has the ball after the passes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shot: Option<Shot>,
+    /// What the coach wants the players to notice in the step
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub notes: String,
+}
+
+impl Step {
+    pub fn new(actors: Vec<Actor>) -> Self {
+        Self {
+            actors,
+            duration: DEFAULT_DURATION,
+            carrier: None,
+            passes: Vec::new(),
+            shot: None,
+            notes: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Play {
+    #[serde(rename = "frames")]
+    pub steps: Vec<Step>,
+    /// Keep every step starting where the previous one ended, also when editing
+    #[serde(default)]
+    pub linked: bool,
+    /// How much of the court the play is shown on
+    #[serde(default)]
+    pub view: FieldView,
+}
+
+impl Default for Play {
+    /// A 3-3 attack against a 6-0 defense
+    fn default() -> Self {
+        let attack = Formation::preset_named(Team::Attack, "3-3");
+        let defense = Formation::preset_named(Team::Defense, "6-0");
+        let mut actors: Vec<Actor> = attack.actors().chain(defense.actors()).collect();
+        actors.push(Actor::still(Point::new(10.0, 13.0), "", PlayerType::Ball));
+        Self::new(actors)
+    }
+}
+
+impl Play {
+    /// A play with a single step
+    pub fn new(actors: Vec<Actor>) -> Self {
+        Self {
+            steps: vec![Step::new(actors)],
+            linked: false,
+            view: FieldView::default(),
+        }
+    }
+
+    /// Length of the play in seconds
+    pub fn duration(&self) -> f32 {
+        self.steps.iter().map(|step| step.duration).sum()
+    }
+
+    /// The time each step starts at, in seconds
+    pub fn step_starts(&self) -> Vec<f32> {
+        self.steps
+            .iter()
+            .scan(0.0, |start, step| {
+                let step_start = *start;
+                *start += step.duration;
+                Some(step_start)
+            })
+            .collect()
+    }
+
+    /// The step playing at `time`, and how far into it we are as a fraction
+    pub fn step_at(&self, time: f32) -> (usize, f32) {
+        let mut start = 0.0;
+        for (idx, step) in self.steps.iter().enumerate() {
+            let end = start + step.duration;
+            if time < end || idx == self.steps.len() - 1 {
+                let frac = (time - start) / step.duration;
+                return (idx, frac.clamp(0.0, 1.0));
+            }
+            start = end;
+        }
+        unreachable!("a play always has at least one step")
+    }
+
+    /// Where everyone is `time` seconds into the play
+    pub fn positions_at(&self, time: f32) -> Vec<Point> {
+        let (step_idx, frac) = self.step_at(time);
+        self.steps[step_idx].positions(frac)
+    }
+
+    /// Inserts a step at `idx` where everyone stands still where they ended
+    /// in the step before. A step inserted first has everyone stand still
+    /// where the play started, with the ball where it started.
+    pub fn insert_step(&mut self, idx: usize) {
+        if idx == 0 {
+            let first = &self.steps[0];
+            let actors = first
+                .actors
+                .iter()
+                .map(|actor| Actor {
+                    path: Path::None(actor.path.start()),
+                    unlinked: false,
+                    timing: Timing::default(),
+                    smooth: false,
+                    ..actor.clone()
+                })
+                .collect();
+            let step = Step {
+                carrier: first.carrier,
+                ..Step::new(actors)
+            };
+            self.steps.insert(0, step);
+            self.relink(1);
+            return;
+        }
+
+        let actors = self.steps[idx - 1]
+            .actors
+            .iter()
+            .map(|prev| {
+                let mut actor = Actor::from_prev(prev.clone());
+                if !prev.smooth {
+                    actor.path = Path::None(prev.path.end());
+                }
+                actor
+            })
+            .collect();
+
+        self.steps.insert(idx, Step::new(actors));
+        self.relink(idx + 1);
+    }
+
+    /// Inserts a copy of step `idx` after it, where everyone moves the same way
+    /// again from where they ended
+    pub fn duplicate_step(&mut self, idx: usize) {
+        let mut copy = self.steps[idx].clone();
+        for actor in copy.actors.iter_mut() {
+            let d = actor.path.end() - actor.path.start();
+            actor.path.translate(d);
+            actor.path.clamp_to(&save::COORD_RANGE);
+            actor.unlinked = false;
+        }
+        self.steps.insert(idx + 1, copy);
+        self.relink(idx + 1);
+    }
+
+    /// Deletes step `idx`, unless it is the only one
+    pub fn delete_step(&mut self, idx: usize) {
+        if self.steps.len() == 1 {
+            return;
+        }
+        self.steps.remove(idx);
+        self.relink(idx);
+    }
+
+    /// Moves step `from` so that it becomes step `to`
+    pub fn move_step(&mut self, from: usize, to: usize) {
+        let step = self.steps.remove(from);
+        self.steps.insert(to, step);
+        self.relink(from.min(to));
+    }
+
+    /// Adds an actor standing still at `pt` in every step
+    pub fn add_actor(&mut self, pt: Point, p_type: PlayerType) {
+        for step in self.steps.iter_mut() {
+            step.actors.push(Actor::still(pt, "", p_type));
+        }
+    }
+
+    /// Removes actor `idx` from every step
+    pub fn remove_actor(&mut self, idx: usize) {
+        for step in self.steps.iter_mut() {
+            step.forget_actor(idx);
+            step.actors.remove(idx);
+        }
+    }
+
+    /// Puts actor `idx` where it stands in step `step_idx` in every step
+    pub fn place_in_every_step(&mut self, idx: usize, step_idx: usize) {
+        let pt = self.steps[step_idx].actors[idx].path.start();
+        for step in self.steps.iter_mut() {
+            step.actors[idx].path = Path::None(pt);
+        }
+    }
+
+    /// Makes every step start with the ball where the step before ended with it,
+    /// and drops passes and shots from players that do not have the ball
+    pub fn pass_on_possession(&mut self) {
+        for idx in 0..self.steps.len() {
+            if idx > 0 {
+                if let Some(carrier) = self.steps[idx - 1].final_carrier() {
+                    self.steps[idx].carrier = Some(carrier);
+                }
+            }
+            let step = &mut self.steps[idx];
+            let mut carrier = step.carrier;
+            let valid = step
+                .passes
+                .iter()
+                .take_while(|pass| {
+                    let valid = carrier == Some(pass.from) && pass.from != pass.to;
+                    carrier = Some(pass.to);
+                    valid
+                })
+                .count();
+            step.passes.truncate(valid);
+
+            let last_catch = step.passes.last().map_or(0.0, |pass| pass.catch);
+            if carrier.is_none() {
+                step.shot = None;
+            } else if let Some(shot) = &mut step.shot {
+                shot.time = shot.time.max(last_catch);
+            }
+        }
+    }
+
+    /// Makes every step from `from` and out start where the previous step ended,
+    /// so that the play stays continuous. Actors that are unlinked are left alone.
+    pub fn relink(&mut self, from: usize) {
+        for idx in from.max(1)..self.steps.len() {
+            let (before, after) = self.steps.split_at_mut(idx);
+            let prev_step = before[idx - 1].actors.iter();
+            for (prev, actor) in prev_step.zip(after[0].actors.iter_mut()) {
+                if !actor.unlinked {
+                    actor.path.set_start(prev.path.end());
+                }
+            }
+        }
+        self.smooth_joins();
+        self.pass_on_possession();
+    }
+
+    /// Turns the start of every smooth actor's path to continue in the direction
+    /// its path in the previous step ends in
+    pub fn smooth_joins(&mut self) {
+        for idx in 1..self.steps.len() {
+            let (before, after) = self.steps.split_at_mut(idx);
+            let prev_step = before[idx - 1].actors.iter();
+            for (prev, actor) in prev_step.zip(after[0].actors.iter_mut()) {
+                let (Path::Spline(prev_spline), Path::Spline(spline)) =
+                    (&prev.path, &mut actor.path)
+                else {
+                    continue;
+                };
+                if !actor.smooth || spline.start() != prev_spline.end() {
+                    continue;
+                }
+                if let Some(dir) = prev_spline.end_direction() {
+                    spline.set_start_direction(dir);
+                }
+            }
+        }
+    }
+
+    /// Sets whether actor `idx` is smooth across steps, in every step
+    pub fn set_smooth(&mut self, idx: usize, smooth: bool) {
+        for step in self.steps.iter_mut() {
+            step.actors[idx].smooth = smooth;
+        }
+        self.smooth_joins();
+    }
+
+    /// Links actor `idx` in step `step_idx` to the previous step again, moving
+    /// its start to where it ended there
+    pub fn link_actor(&mut self, step_idx: usize, idx: usize) {
+        let end = step_idx
+            .checked_sub(1)
+            .map(|prev| self.steps[prev].actors[idx].path.end());
+        let actor = &mut self.steps[step_idx].actors[idx];
+        actor.unlinked = false;
+        if let Some(end) = end {
+            actor.path.set_start(end);
+        }
+    }
+
+    /// Unlinks the actors in step `idx` that were moved away from where they
+    /// ended in the previous step
+    pub fn unlink_moved(&mut self, idx: usize) {
+        if idx == 0 {
+            return;
+        }
+        let (before, after) = self.steps.split_at_mut(idx);
+        let prev_step = before[idx - 1].actors.iter();
+        for (prev, actor) in prev_step.zip(after[0].actors.iter_mut()) {
+            if actor.path.start() != prev.path.end() {
+                actor.unlinked = true;
+            }
+        }
+    }
+}
+
+/// The default play, where the first attacker has the ball when it starts.
+/// Returns it with the indices of the attackers, in order.
+#[cfg(test)]
+pub(crate) fn attack_with_ball() -> (Play, Vec<usize>) {
+    let mut play = Play::default();
+    let step = &mut play.steps[0];
+    let attackers: Vec<usize> = (0..step.actors.len())
+        .filter(|&idx| step.actors[idx].p_type == PlayerType::Attacking)
+        .collect();
+    step.carrier = Some(attackers[0]);
+    (play, attackers)
+}
+
+#[cfg(test)]
+mod tests {
+    use emath::Vec2;
+
+    use super::*;
+
+    #[test]
+    fn removing_the_ball_drops_possession_in_every_step() {
+        let (mut play, _) = attack_with_ball();
+        play.insert_step(1);
+        assert!(play.steps[1].carrier.is_some());
+        let ball = play.steps[0].ball().unwrap();
+
+        play.remove_actor(ball);
+
+        assert!(play.steps.iter().all(|step| step.carrier.is_none()));
+        let reopened = save::from_json(&save::to_json(&play)).unwrap();
+        assert_eq!(reopened, play);
+    }
+
+    #[test]
+    fn the_ball_stays_with_whoever_had_it_at_the_end_of_the_step() {
+        let (mut play, attackers) = attack_with_ball();
+        let pass = Pass {
+            from: attackers[0],
+            to: attackers[1],
+            throw: 0.2,
+            catch: 0.5,
+            flight: Default::default(),
+        };
+        play.steps[0].passes.push(pass.clone());
+
+        play.insert_step(1);
+        assert_eq!(play.steps[1].carrier, Some(attackers[1]));
+
+        // Only the player that has the ball can pass it on
+        play.steps[1].passes.push(pass);
+        play.pass_on_possession();
+        assert!(play.steps[1].passes.is_empty());
+    }
+
+    #[test]
+    fn a_step_inserted_first_starts_where_the_play_started() {
+        let (mut play, attackers) = attack_with_ball();
+        let start = play.positions_at(0.0);
+
+        play.insert_step(0);
+
+        assert_eq!(play.steps.len(), 2);
+        assert_eq!(play.steps[0].positions(1.0), start);
+        assert_eq!(play.steps[0].carrier, Some(attackers[0]));
+        assert_eq!(play.steps[1].carrier, Some(attackers[0]));
+    }
+
+    #[test]
+    fn a_duplicated_step_continues_the_movement() {
+        let pts = [(2.0, 2.0), (3.0, 2.0), (5.0, 5.0), (6.0, 6.0)].map(|(x, y)| Point::new(x, y));
+        let mut play = Play::new(vec![Actor::moving(pts, "A", PlayerType::Attacking)]);
+
+        play.duplicate_step(0);
+
+        let moved = pts.map(|pt| pt + Vec2::new(4.0, 4.0));
+        assert_eq!(play.steps[1].actors[0].path.points(), moved);
+    }
+
+    #[test]
+    fn linking_an_actor_again_keeps_it_linked() {
+        let mut play = Play::default();
+        play.insert_step(1);
+        play.steps[1].actors[0].path.translate(Vec2::new(3.0, 0.0));
+        play.unlink_moved(1);
+        assert!(play.steps[1].actors[0].unlinked);
+
+        play.link_actor(1, 0);
+        play.unlink_moved(1);
+
+        let actor = &play.steps[1].actors[0];
+        assert!(!actor.unlinked);
+        assert_eq!(actor.path.start(), play.steps[0].actors[0].path.end());
+    }
+}
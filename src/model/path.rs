@@ -0,0 +1,87 @@
+//! Where an actor goes during a step.
+
+use std::ops::RangeInclusive;
+
+use emath::Vec2;
+
+use super::{spline::Spline, Point};
+
+/// Where an [`Actor`](super::Actor) goes during a step, or the point it stands
+/// still at
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum Path {
+    None(Point),
+    Spline(Spline),
+}
+
+impl Path {
+    pub fn start(&self) -> Point {
+        match self {
+            Path::None(pt) => *pt,
+            Path::Spline(spline) => spline.start(),
+        }
+    }
+
+    pub fn end(&self) -> Point {
+        match self {
+            Path::None(pt) => *pt,
+            Path::Spline(spline) => spline.end(),
+        }
+    }
+
+    /// Moves the start point, keeping the direction the path starts in
+    pub fn set_start(&mut self, start: Point) {
+        match self {
+            Path::None(pt) => *pt = start,
+            Path::Spline(spline) => spline.set_start(start),
+        }
+    }
+
+    /// Moves the whole path
+    pub fn translate(&mut self, d: Vec2) {
+        match self {
+            Path::None(pt) => *pt += d,
+            Path::Spline(spline) => spline.translate(d),
+        }
+    }
+
+    /// All points defining the path
+    pub fn points(&self) -> &[Point] {
+        match self {
+            Path::None(pt) => std::slice::from_ref(pt),
+            Path::Spline(spline) => &spline.points,
+        }
+    }
+
+    /// All points defining the path, to change them
+    pub fn points_mut(&mut self) -> &mut [Point] {
+        match self {
+            Path::None(pt) => std::slice::from_mut(pt),
+            Path::Spline(spline) => &mut spline.points,
+        }
+    }
+
+    /// Length of the path, in field coordinates
+    pub fn length(&self) -> f32 {
+        match self {
+            Path::None(_) => 0.0,
+            Path::Spline(spline) => spline.length(),
+        }
+    }
+
+    /// The point a fraction `s` of the length along the path
+    pub fn at(&self, s: f32) -> Point {
+        match self {
+            Path::None(pt) => *pt,
+            Path::Spline(spline) => spline.at(s),
+        }
+    }
+
+    /// Moves the points that are outside `range` in either coordinate onto its edge
+    pub fn clamp_to(&mut self, range: &RangeInclusive<f32>) {
+        for pt in self.points_mut() {
+            pt.x = pt.x.clamp(*range.start(), *range.end());
+            pt.y = pt.y.clamp(*range.start(), *range.end());
+        }
+    }
+}
@@ -0,0 +1,431 @@
+//! Versioned (de)serialization of a [`Play`].
+//!
+//! Plays are saved as JSON inside documents that record the schema version,
+//! e.g. `{"version": 1, "animation": { ... }}`. When the layout of [`Play`],
+//! [`Actor`](super::Actor) or [`Path`] changes, push a step to `MIGRATIONS`
+//! that upgrades the previous layout, so that old saved state still loads.
+
+use std::{fmt, ops::RangeInclusive};
+
+use serde_json::{json, Map, Value};
+
+use super::{spline::MAX_SEGMENTS, Path, Play, Step, DURATION_RANGE};
+
+/// Upgrades a play from one schema version to the next
+type Migration = fn(Value) -> Result<Value, LoadError>;
+
+/// Upgrades from schema version `i + 1` to `i + 2`, applied in order.
+const MIGRATIONS: &[Migration] = &[v1_frame_durations, v2_splines, v3_meters];
+
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32 + 1;
+
+/// Most steps a play can have
+pub const MAX_FRAMES: usize = 100;
+pub const MAX_LABEL_LEN: usize = 16;
+pub const MAX_NAME_LEN: usize = 32;
+/// Highest jersey number
+pub const MAX_NUMBER: u32 = 99;
+/// Points may be outside the court, since control points often are
+pub const COORD_RANGE: RangeInclusive<f32> = -20.0..=60.0;
+
+#[derive(Debug)]
+pub enum LoadError {
+    Json(serde_json::Error),
+    UnsupportedVersion(u32),
+    Invalid(String),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Json(err) => write!(f, "invalid data: {err}"),
+            LoadError::UnsupportedVersion(v) => write!(
+                f,
+                "unsupported version {v} (this version of jugge reads up to {SCHEMA_VERSION})"
+            ),
+            LoadError::Invalid(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl From<serde_json::Error> for LoadError {
+    fn from(err: serde_json::Error) -> Self {
+        LoadError::Json(err)
+    }
+}
+
+#[derive(serde::Serialize)]
+struct SavedRef<'a> {
+    version: u32,
+    animation: &'a Play,
+}
+
+#[derive(serde::Deserialize)]
+struct Saved {
+    version: u32,
+    animation: Value,
+}
+
+pub fn to_json(play: &Play) -> String {
+    serde_json::to_string(&SavedRef {
+        version: SCHEMA_VERSION,
+        animation: play,
+    })
+    .expect("play should always be serializable")
+}
+
+pub fn from_json(json: &str) -> Result<Play, LoadError> {
+    let saved: Saved = serde_json::from_str(json)?;
+    let value = migrate(saved.animation, saved.version)?;
+    let play: Play = serde_json::from_value(value)?;
+    check(&play)?;
+    Ok(play)
+}
+
+/// Checks that a freshly loaded play is usable
+pub fn check(play: &Play) -> Result<(), LoadError> {
+    let invalid = |msg: String| Err(LoadError::Invalid(msg));
+
+    if play.steps.is_empty() {
+        return invalid("the play has no steps".to_owned());
+    }
+    if play.steps.len() > MAX_FRAMES {
+        return invalid(format!(
+            "the play has {} steps, but at most {MAX_FRAMES} are supported",
+            play.steps.len()
+        ));
+    }
+
+    let num_actors = play.steps[0].actors.len();
+    for (idx, frame) in play.steps.iter().enumerate() {
+        let step = idx + 1;
+        if frame.actors.len() != num_actors {
+            return invalid(format!(
+                "step {step} has {} players, but step 1 has {num_actors}",
+                frame.actors.len()
+            ));
+        }
+        if !DURATION_RANGE.contains(&frame.duration) {
+            return invalid(format!(
+                "step {step} lasts {} seconds, but it must last between {} and {} seconds",
+                frame.duration,
+                DURATION_RANGE.start(),
+                DURATION_RANGE.end()
+            ));
+        }
+        for actor in &frame.actors {
+            let timing = actor.timing;
+            if !(0.0 <= timing.start && timing.start <= timing.end && timing.end <= 1.0) {
+                return invalid(format!(
+                    "step {step}: \"{}\" has invalid timing {}..{}",
+                    actor.label, timing.start, timing.end
+                ));
+            }
+            let chars = actor.label.chars().count();
+            if chars > MAX_LABEL_LEN {
+                return invalid(format!(
+                    "step {step}: the label \"{}\" is {chars} characters long, but at most {MAX_LABEL_LEN} are allowed",
+                    actor.label
+                ));
+            }
+            let chars = actor.player_name.chars().count();
+            if chars > MAX_NAME_LEN {
+                return invalid(format!(
+                    "step {step}: the name \"{}\" is {chars} characters long, but at most {MAX_NAME_LEN} are allowed",
+                    actor.player_name
+                ));
+            }
+            if let Some(number) = actor.number.filter(|&number| number > MAX_NUMBER) {
+                return invalid(format!(
+                    "step {step}: \"{}\" has number {number}, but the highest allowed is {MAX_NUMBER}",
+                    actor.name()
+                ));
+            }
+            if let Path::Spline(spline) = &actor.path {
+                if !actor.p_type.can_move() {
+                    return invalid(format!(
+                        "step {step}: \"{}\" is a {}, which cannot move",
+                        actor.name(),
+                        actor.p_type.name()
+                    ));
+                }
+                let num_points = spline.points.len();
+                if num_points < 4 || num_points % 3 != 1 {
+                    return invalid(format!(
+                        "step {step}: the path of \"{}\" has {num_points} points, \
+                         but a path has 3 points per segment plus one",
+                        actor.label
+                    ));
+                }
+                if spline.num_segments() > MAX_SEGMENTS {
+                    return invalid(format!(
+                        "step {step}: the path of \"{}\" has more than {MAX_SEGMENTS} segments",
+                        actor.label
+                    ));
+                }
+                if spline.sharp.len() > spline.num_segments() + 1 {
+                    return invalid(format!(
+                        "step {step}: the path of \"{}\" has more corners than waypoints",
+                        actor.label
+                    ));
+                }
+            }
+            for pt in actor.path.points() {
+                let in_range = |v: f32| COORD_RANGE.contains(&v);
+                if !(in_range(pt.x) && in_range(pt.y)) {
+                    return invalid(format!(
+                        "step {step}: \"{}\" is placed at ({}, {}), which is outside the field",
+                        actor.label, pt.x, pt.y
+                    ));
+                }
+            }
+        }
+        check_passes(frame, step)?;
+    }
+
+    Ok(())
+}
+
+/// Checks that the ball is held, passed and shot by players that exist, one pass at a time
+fn check_passes(frame: &Step, step: usize) -> Result<(), LoadError> {
+    let invalid = |msg: String| Err(LoadError::Invalid(msg));
+    let holds_ball = |idx: usize| {
+        frame
+            .actors
+            .get(idx)
+            .is_some_and(|actor| actor.p_type.can_hold_ball())
+    };
+
+    if frame.carrier.is_none() && !frame.passes.is_empty() {
+        return invalid(format!("step {step} has passes, but nobody has the ball"));
+    }
+    if frame.carrier.is_some() && frame.ball().is_none() {
+        return invalid(format!(
+            "step {step}: someone has the ball, but there is no ball"
+        ));
+    }
+    if let Some(carrier) = frame.carrier.filter(|&carrier| !holds_ball(carrier)) {
+        return invalid(format!(
+            "step {step}: the ball is held by player {carrier}, who cannot hold it"
+        ));
+    }
+
+    let mut holder = frame.carrier;
+    let mut last_catch = 0.0;
+    for pass in &frame.passes {
+        if holder != Some(pass.from) || !holds_ball(pass.to) || pass.from == pass.to {
+            return invalid(format!(
+                "step {step}: player {} cannot pass to player {}",
+                pass.from, pass.to
+            ));
+        }
+        if !(last_catch <= pass.throw && pass.throw <= pass.catch && pass.catch <= 1.0) {
+            return invalid(format!(
+                "step {step}: a pass has invalid timing {}..{}",
+                pass.throw, pass.catch
+            ));
+        }
+        holder = Some(pass.to);
+        last_catch = pass.catch;
+    }
+    if let Some(shot) = &frame.shot {
+        if frame.carrier.is_none() {
+            return invalid(format!("step {step} has a shot, but nobody has the ball"));
+        }
+        if !(last_catch <= shot.time && shot.time <= 1.0) {
+            return invalid(format!(
+                "step {step}: the shot is taken at {}, before the ball is caught or after the step",
+                shot.time
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// A saved value that is not what its schema version has there
+fn unexpected(what: &str) -> LoadError {
+    LoadError::Invalid(format!("expected {what}"))
+}
+
+/// The steps of a saved play, in every version
+fn frames(animation: &mut Value) -> Result<&mut Vec<Value>, LoadError> {
+    animation
+        .as_object_mut()
+        .and_then(|animation| animation.get_mut("frames"))
+        .and_then(Value::as_array_mut)
+        .ok_or_else(|| unexpected("the steps to be a list"))
+}
+
+/// Every person of every step, from version 2 on
+fn persons(animation: &mut Value) -> Result<Vec<&mut Map<String, Value>>, LoadError> {
+    let mut persons = Vec::new();
+    for frame in frames(animation)? {
+        let frame_persons = frame
+            .as_object_mut()
+            .and_then(|frame| frame.get_mut("persons"))
+            .and_then(Value::as_array_mut)
+            .ok_or_else(|| unexpected("every step to have a list of players"))?;
+        for person in frame_persons {
+            let person = person
+                .as_object_mut()
+                .ok_or_else(|| unexpected("every player to be an object"))?;
+            persons.push(person);
+        }
+    }
+    Ok(persons)
+}
+
+/// Version 2 gave each step a duration, so the players of a step are wrapped in a struct
+fn v1_frame_durations(mut animation: Value) -> Result<Value, LoadError> {
+    for frame in frames(&mut animation)? {
+        let persons = frame.take();
+        *frame = json!({ "persons": persons, "duration": 1.0 });
+    }
+    Ok(animation)
+}
+
+/// Version 3 let paths have several segments, so single bezier curves became splines
+fn v2_splines(mut animation: Value) -> Result<Value, LoadError> {
+    for person in persons(&mut animation)? {
+        let Some(movement) = person.get_mut("movement") else {
+            continue;
+        };
+        if let Some(points) = movement.get_mut("Bezier").map(Value::take) {
+            *movement = json!({ "Spline": { "points": points } });
+        }
+    }
+    Ok(animation)
+}
+
+/// Version 4 measured coordinates in meters, instead of as fractions of the field width
+fn v3_meters(mut animation: Value) -> Result<Value, LoadError> {
+    const FIELD_WIDTH: f64 = 20.0;
+    let scale = |pt: &mut Value| {
+        let Some(pt) = pt.as_object_mut() else {
+            return;
+        };
+        for coord in ["x", "y"] {
+            if let Some(v) = pt.get(coord).and_then(Value::as_f64) {
+                pt.insert(coord.to_owned(), json!(v * FIELD_WIDTH));
+            }
+        }
+    };
+
+    for person in persons(&mut animation)? {
+        let Some(movement) = person.get_mut("movement") else {
+            continue;
+        };
+        if let Some(pt) = movement.get_mut("None") {
+            scale(pt);
+        }
+        let spline = movement.get_mut("Spline");
+        if let Some(points) = spline
+            .and_then(|spline| spline.get_mut("points"))
+            .and_then(Value::as_array_mut)
+        {
+            points.iter_mut().for_each(scale);
+        }
+    }
+    Ok(animation)
+}
+
+/// Upgrades an animation saved with schema `version` to the current layout
+pub fn migrate(mut value: Value, version: u32) -> Result<Value, LoadError> {
+    if version == 0 || version > SCHEMA_VERSION {
+        return Err(LoadError::UnsupportedVersion(version));
+    }
+    for step in &MIGRATIONS[(version - 1) as usize..] {
+        value = step(value)?;
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{
+        attack_with_ball,
+        ball::{Flight, Pass},
+        Point,
+    };
+
+    #[test]
+    fn a_version_1_play_is_migrated_to_the_current_layout() {
+        let json = r#"{"version": 1, "animation": {"frames": [[
+            {"movement": {"None": {"x": 0.5, "y": 0.25}}, "label": "CB", "p_type": "Attacking"},
+            {"movement": {"Bezier": [{"x": 0.1, "y": 0.1}, {"x": 0.2, "y": 0.1},
+                                     {"x": 0.3, "y": 0.2}, {"x": 0.4, "y": 0.4}]},
+             "label": "LB", "p_type": "Attacking"}
+        ]], "cur_frame": 0}}"#;
+
+        let play = from_json(json).unwrap();
+
+        let step = &play.steps[0];
+        assert_eq!(step.duration, 1.0);
+        assert_eq!(step.actors[0].path, Path::None(Point::new(10.0, 5.0)));
+        let meters =
+            [(2.0, 2.0), (4.0, 2.0), (6.0, 4.0), (8.0, 8.0)].map(|(x, y)| Point::new(x, y));
+        assert_eq!(step.actors[1].path.points(), meters);
+    }
+
+    #[test]
+    fn unknown_versions_are_rejected() {
+        for version in [0, SCHEMA_VERSION + 1] {
+            let result = migrate(json!({}), version);
+            assert!(matches!(result, Err(LoadError::UnsupportedVersion(v)) if v == version));
+        }
+    }
+
+    #[test]
+    fn a_saved_play_opens_the_same() {
+        let play = Play::default();
+        assert_eq!(from_json(&to_json(&play)).unwrap(), play);
+    }
+
+    fn pass(from: usize, to: usize, throw: f32, catch: f32) -> Pass {
+        Pass {
+            from,
+            to,
+            throw,
+            catch,
+            flight: Flight::Straight,
+        }
+    }
+
+    #[test]
+    fn the_ball_can_be_passed_on_one_player_at_a_time() {
+        let (mut play, attackers) = attack_with_ball();
+        let passes = &mut play.steps[0].passes;
+        passes.push(pass(attackers[0], attackers[1], 0.1, 0.3));
+        passes.push(pass(attackers[1], attackers[2], 0.4, 0.6));
+        assert!(check(&play).is_ok());
+    }
+
+    #[test]
+    fn only_the_player_with_the_ball_can_pass() {
+        let (mut play, attackers) = attack_with_ball();
+        let passes = &mut play.steps[0].passes;
+        passes.push(pass(attackers[1], attackers[2], 0.1, 0.3));
+        assert!(check(&play).is_err());
+    }
+
+    #[test]
+    fn the_ball_cannot_be_passed_to_itself() {
+        let (mut play, attackers) = attack_with_ball();
+        let step = &mut play.steps[0];
+        let ball = step.ball().unwrap();
+        step.passes.push(pass(attackers[0], ball, 0.1, 0.3));
+        assert!(check(&play).is_err());
+    }
+
+    #[test]
+    fn a_pass_cannot_be_thrown_before_the_last_one_is_caught() {
+        let (mut play, attackers) = attack_with_ball();
+        let passes = &mut play.steps[0].passes;
+        passes.push(pass(attackers[0], attackers[1], 0.1, 0.5));
+        passes.push(pass(attackers[1], attackers[2], 0.4, 0.6));
+        assert!(check(&play).is_err());
+    }
+}
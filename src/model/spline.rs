@@ -0,0 +1,301 @@
+use emath::Vec2;
+
+use super::{
+    bezier::{bez_at_distance, bez_at_t, bez_length, bez_split},
+    Point,
+};
+
+/// Most segments a single path can have
+pub const MAX_SEGMENTS: usize = 32;
+
+/// Samples per segment when looking for the closest point on the path
+const CLOSEST_SAMPLES: usize = 32;
+
+/// A path made of cubic bezier curves joined end to end.
+///
+/// Segment `i` is given by `points[3 * i..=3 * i + 3]`, so every third point
+/// is a knot the path passes through, and the points between them are handles.
+/// The path is smooth through a knot unless the knot is sharp.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Spline {
+    pub points: Vec<Point>,
+    /// Indexed by knot, missing entries are smooth
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sharp: Vec<bool>,
+}
+
+impl Spline {
+    pub fn new(pts: [Point; 4]) -> Self {
+        Self {
+            points: pts.to_vec(),
+            sharp: Vec::new(),
+        }
+    }
+
+    pub fn is_knot(idx: usize) -> bool {
+        idx % 3 == 0
+    }
+
+    pub fn num_segments(&self) -> usize {
+        (self.points.len() - 1) / 3
+    }
+
+    pub fn segment(&self, idx: usize) -> [Point; 4] {
+        let pts = &self.points[3 * idx..=3 * idx + 3];
+        [pts[0], pts[1], pts[2], pts[3]]
+    }
+
+    pub fn segments(&self) -> impl Iterator<Item = [Point; 4]> + '_ {
+        (0..self.num_segments()).map(|idx| self.segment(idx))
+    }
+
+    pub fn start(&self) -> Point {
+        self.points[0]
+    }
+
+    pub fn end(&self) -> Point {
+        self.points[self.points.len() - 1]
+    }
+
+    /// Whether `knot` is between two segments, and not an end of the path
+    pub fn is_interior(&self, knot: usize) -> bool {
+        0 < knot && knot < self.num_segments()
+    }
+
+    pub fn is_sharp(&self, knot: usize) -> bool {
+        self.sharp.get(knot).copied().unwrap_or(false)
+    }
+
+    pub fn length(&self) -> f32 {
+        self.segments().map(bez_length).sum()
+    }
+
+    /// The point a fraction `s` of the length along the path
+    pub fn at(&self, s: f32) -> Point {
+        let lengths: Vec<f32> = self.segments().map(bez_length).collect();
+        let mut target = s.clamp(0.0, 1.0) * lengths.iter().sum::<f32>();
+        for (segment, &length) in self.segments().zip(&lengths) {
+            if target <= length && length > 0.0 {
+                return bez_at_distance(segment, target / length);
+            }
+            target -= length;
+        }
+        self.end()
+    }
+
+    /// The closest point on the path to `pt`, as the segment it is on, its
+    /// `t` along that segment, and the point itself
+    pub fn closest(&self, pt: Point) -> (usize, f32, Point) {
+        let mut closest = (0, 0.0, self.start());
+        let mut closest_dist = f32::INFINITY;
+        for (idx, segment) in self.segments().enumerate() {
+            for i in 0..=CLOSEST_SAMPLES {
+                let t = i as f32 / CLOSEST_SAMPLES as f32;
+                let candidate = bez_at_t(segment, t);
+                let dist = candidate.distance_sq(pt);
+                if dist < closest_dist {
+                    closest = (idx, t, candidate);
+                    closest_dist = dist;
+                }
+            }
+        }
+        closest
+    }
+
+    pub fn translate(&mut self, d: Vec2) {
+        for pt in self.points.iter_mut() {
+            *pt += d;
+        }
+    }
+
+    /// Moves the start point, keeping the direction the path starts in
+    pub fn set_start(&mut self, start: Point) {
+        let d = start - self.points[0];
+        self.points[1] += d;
+        self.points[0] = start;
+    }
+
+    /// The direction the path ends in, if it has one
+    pub fn end_direction(&self) -> Option<Vec2> {
+        let end = self.end();
+        let handle = self.points[self.points.len() - 2];
+        let dir = (end - handle).normalized();
+        (dir.is_finite() && dir != Vec2::ZERO).then_some(dir)
+    }
+
+    /// Turns the first handle to point along `dir`, keeping its length
+    pub fn set_start_direction(&mut self, dir: Vec2) {
+        let length = (self.points[1] - self.points[0]).length();
+        self.points[1] = self.points[0] + dir * length;
+    }
+
+    /// Moves point `idx`. Knots bring their handles along, and moving a handle
+    /// of a smooth knot turns the handle on the other side to keep it smooth.
+    pub fn move_point(&mut self, idx: usize, d: Vec2) {
+        self.points[idx] += d;
+        if Self::is_knot(idx) {
+            if idx > 0 {
+                self.points[idx - 1] += d;
+            }
+            if idx + 1 < self.points.len() {
+                self.points[idx + 1] += d;
+            }
+        } else {
+            let knot = if idx % 3 == 1 { idx - 1 } else { idx + 1 };
+            self.align_handles(knot / 3, idx);
+        }
+    }
+
+    /// Turns the other handle of a smooth interior `knot` to point directly
+    /// away from handle `fixed`, keeping its length
+    fn align_handles(&mut self, knot: usize, fixed: usize) {
+        if !self.is_interior(knot) || self.is_sharp(knot) {
+            return;
+        }
+        let knot_pt = self.points[3 * knot];
+        let other = 6 * knot - fixed;
+        let dir = (knot_pt - self.points[fixed]).normalized();
+        let length = (self.points[other] - knot_pt).length();
+        if dir.is_finite() && dir != Vec2::ZERO {
+            self.points[other] = knot_pt + dir * length;
+        }
+    }
+
+    /// Splits `segment` in two at `t`, without changing the shape of the path
+    pub fn insert_knot(&mut self, segment: usize, t: f32) {
+        let (before, after) = bez_split(self.segment(segment), t);
+        let start = 3 * segment;
+        self.points.splice(
+            start..=start + 3,
+            before.into_iter().chain(after.into_iter().skip(1)),
+        );
+        let knot = segment + 1;
+        if knot < self.sharp.len() {
+            self.sharp.insert(knot, false);
+        }
+    }
+
+    /// Joins the two segments meeting at the interior `knot`
+    pub fn remove_knot(&mut self, knot: usize) {
+        if !self.is_interior(knot) {
+            return;
+        }
+        self.points.drain(3 * knot - 1..=3 * knot + 1);
+        if knot < self.sharp.len() {
+            self.sharp.remove(knot);
+        }
+    }
+
+    /// Makes `knot` a sharp corner, or makes the path smooth through it
+    pub fn set_sharp(&mut self, knot: usize, sharp: bool) {
+        if self.sharp.len() <= knot {
+            self.sharp.resize(knot + 1, false);
+        }
+        self.sharp[knot] = sharp;
+        while self.sharp.last() == Some(&false) {
+            self.sharp.pop();
+        }
+        // The ends have a single handle, with nothing to line up
+        if !sharp && self.is_interior(knot) {
+            self.align_handles(knot, 3 * knot - 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pt(x: f32, y: f32) -> Point {
+        Point::new(x, y)
+    }
+
+    /// Straight segments along the x axis, with a knot at every third x
+    fn straight(xs: &[f32]) -> Spline {
+        Spline {
+            points: xs.iter().map(|&x| pt(x, 0.0)).collect(),
+            sharp: Vec::new(),
+        }
+    }
+
+    fn assert_near(a: Point, b: Point) {
+        assert!(a.distance(b) < 0.05, "{a:?} is not near {b:?}");
+    }
+
+    #[test]
+    fn points_are_sampled_by_length_across_segments() {
+        // The second segment is twice as long as the first
+        let spline = straight(&[0.0, 1.0, 2.0, 3.0, 5.0, 7.0, 9.0]);
+        assert_near(spline.at(0.0), pt(0.0, 0.0));
+        assert_near(spline.at(1.0 / 3.0), pt(3.0, 0.0));
+        assert_near(spline.at(2.0 / 3.0), pt(6.0, 0.0));
+        assert_near(spline.at(1.0), pt(9.0, 0.0));
+    }
+
+    #[test]
+    fn inserting_a_knot_keeps_the_shape() {
+        let mut spline = Spline::new([pt(0.0, 0.0), pt(2.0, 4.0), pt(6.0, 4.0), pt(8.0, 0.0)]);
+        let before: Vec<Point> = (0..=10).map(|i| spline.at(i as f32 / 10.0)).collect();
+
+        spline.insert_knot(0, 0.3);
+
+        assert_eq!(spline.num_segments(), 2);
+        for (i, &expected) in before.iter().enumerate() {
+            assert_near(spline.at(i as f32 / 10.0), expected);
+        }
+    }
+
+    #[test]
+    fn inserting_a_knot_keeps_later_corners() {
+        let mut spline = straight(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        spline.set_sharp(1, true);
+
+        spline.insert_knot(0, 0.5);
+
+        assert!(!spline.is_sharp(1));
+        assert!(spline.is_sharp(2));
+    }
+
+    #[test]
+    fn removing_a_knot_joins_its_segments() {
+        let mut spline = straight(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        spline.set_sharp(1, true);
+
+        spline.remove_knot(1);
+
+        assert_eq!(spline.points, [0.0, 1.0, 5.0, 6.0].map(|x| pt(x, 0.0)));
+        assert!(!spline.is_sharp(1));
+    }
+
+    #[test]
+    fn the_ends_cannot_be_removed() {
+        let mut spline = straight(&[0.0, 1.0, 2.0, 3.0]);
+        spline.remove_knot(0);
+        spline.remove_knot(1);
+        assert_eq!(spline, straight(&[0.0, 1.0, 2.0, 3.0]));
+    }
+
+    #[test]
+    fn a_knot_made_smooth_lines_up_its_handles() {
+        let mut spline = straight(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        spline.set_sharp(1, true);
+        // The handle on the other side of a corner stays
+        spline.move_point(4, Vec2::new(0.0, 2.0));
+        assert_eq!(spline.points[2], pt(2.0, 0.0));
+
+        spline.set_sharp(1, false);
+
+        let length = 5.0_f32.sqrt();
+        assert_near(spline.points[4], pt(3.0 + length, 0.0));
+        assert!(spline.sharp.is_empty());
+    }
+
+    #[test]
+    fn the_ends_can_be_made_sharp_and_smooth() {
+        let mut spline = straight(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        spline.set_sharp(0, true);
+        spline.set_sharp(0, false);
+        spline.set_sharp(2, false);
+        assert_eq!(spline, straight(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0]));
+    }
+}
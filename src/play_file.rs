@@ -0,0 +1,180 @@
+//! The `.jugge` file format, used to share a single play.
+//!
+//! A `.jugge` file is UTF-8 encoded JSON:
+//!
+//! ```json
+//! {
+//!   "format": "jugge",
+//!   "version": 4,
+//!   "play": {
+//!     "name": "Cross to the left",
+//!     "author": "Coach",
+//!     "created": 1710000000,
+//!     "modified": 1710000000,
+//!     "tags": ["vs 6-0"],
+//!     "description": "Free text",
+//!     "animation": {
+//!       "frames": [
+//!         {
+//!           "persons": [
+//!             {
+//!               "movement": { "None": { "x": 10.0, "y": 12.4 } },
+//!               "label": "CB",
+//!               "p_type": "Attacking",
+//!               "number": 7,
+//!               "player_name": "Kari"
+//!             },
+//!             {
+//!               "movement": {
+//!                 "Spline": { "points": [{ "x": 2.2, "y": 11.2 }, ...], "sharp": [false, true] }
+//!               },
+//!               "label": "LB",
+//!               "p_type": "Attacking",
+//!               "timing": { "start": 0.5, "end": 1.0, "easing": "Sprint" }
+//!             }
+//!           ],
+//!           "duration": 1.5,
+//!           "carrier": 0,
+//!           "passes": [{ "from": 0, "to": 1, "throw": 0.2, "catch": 0.5, "flight": "Lob" }],
+//!           "shot": { "time": 0.8, "target": "UpperLeft", "reaction": "LowRight" },
+//!           "notes": "LB waits for the pivot's block"
+//!         }
+//!       ],
+//!       "linked": false,
+//!       "view": "Half"
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! * `version` is the schema version of the animation, see [`crate::model::save`].
+//!   Files from older versions are migrated when opened.
+//! * `created` and `modified` are seconds since the unix epoch.
+//! * `frames` holds one list of players per step, and every step lists the same
+//!   players in the same order. `duration` is the length of the step in seconds.
+//! * A player either stands still (`None`) or moves along a path of cubic bezier
+//!   curves joined end to end (`Spline`). The path goes through every third
+//!   point, and the points between are the control points of each curve. `sharp`
+//!   lists which of these waypoints are corners, and defaults to none.
+//! * `timing` is when during the step the player moves, as fractions of the
+//!   step duration. It defaults to the whole step. Players move along the path
+//!   at constant speed, unless `easing` is `EaseInOut` or `Sprint`.
+//! * When `smooth` is true, the player's path starts in the direction its path
+//!   in the previous step ended in. It defaults to false.
+//! * `p_type` is one of `Attacking`, `Defending`, `AttackingGoalkeeper`,
+//!   `DefendingGoalkeeper`, `Referee` or `Ball`, or one of the pieces of
+//!   equipment `Cone`, `Pole`, `Mat` or `Rebounder`. Equipment cannot move.
+//! * `number` is the jersey number and `player_name` the name of the player.
+//!   Both are optional.
+//! * `carrier` is the index of the player that has the ball when the step starts,
+//!   and the ball follows that player. It defaults to nobody, and then the ball
+//!   moves by itself. `passes` throws the ball between players, in order, at
+//!   fractions of the step duration. `flight` is `Straight`, `Bounce` or `Lob`,
+//!   and defaults to `Straight`.
+//! * `shot` ends the step with the player that has the ball after the passes
+//!   shooting at the closest goal. `target` is one of `UpperLeft`, `UpperMiddle`,
+//!   `UpperRight`, `MiddleLeft`, `Middle`, `MiddleRight`, `LowLeft`, `LowMiddle`
+//!   or `LowRight`, as seen by the shooter. `reaction` is where the goalkeeper
+//!   goes, in the same terms, and is optional. Nobody has the ball after a shot.
+//! * `notes` are the coach's notes for the step, printed in playbooks. They
+//!   default to none.
+//! * When `linked` is true, every step starts where the previous one ended,
+//!   except for players with `"unlinked": true`. Both default to false.
+//! * `view` is `Half` or `Full`, for plays shown on half the court or all of it.
+//!   It defaults to `Half`.
+//! * Coordinates are in meters, measured from the left corner of the goal line
+//!   at the top. `x` goes across the 20 m wide court, and `y` along the 40 m
+//!   long court, so `(20, 20)` is the right end of the center line.
+
+use crate::library::{self, PlayEntry};
+use crate::model::save::{self, LoadError};
+
+pub const EXTENSION: &str = "jugge";
+
+const FORMAT: &str = "jugge";
+
+#[derive(serde::Serialize)]
+struct PlayFileRef<'a> {
+    format: &'a str,
+    version: u32,
+    play: &'a PlayEntry,
+}
+
+#[derive(serde::Deserialize)]
+struct PlayFile {
+    format: String,
+    version: u32,
+    play: serde_json::Value,
+}
+
+pub fn to_json(play: &PlayEntry) -> String {
+    serde_json::to_string_pretty(&PlayFileRef {
+        format: FORMAT,
+        version: save::SCHEMA_VERSION,
+        play,
+    })
+    .expect("play should always be serializable")
+}
+
+pub fn from_json(json: &str) -> Result<PlayEntry, LoadError> {
+    let file: PlayFile = serde_json::from_str(json)?;
+    if file.format != FORMAT {
+        return Err(LoadError::Invalid(format!(
+            "expected a \"{FORMAT}\" file, found \"{}\"",
+            file.format
+        )));
+    }
+
+    library::play_from_value(file.play, file.version)
+}
+
+/// A file name for the play, without characters that are invalid in paths
+pub fn file_name(play: &PlayEntry) -> String {
+    file_name_with(play, EXTENSION)
+}
+
+/// A file name for the play with another `extension`, for exports
+pub fn file_name_with(play: &PlayEntry, extension: &str) -> String {
+    let name: String = play
+        .name
+        .chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+        .collect();
+    let name = name.trim();
+    let name = if name.is_empty() { "play" } else { name };
+    format!("{name}.{extension}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_invalid(json: &str) {
+        let result = from_json(json);
+        assert!(matches!(result, Err(LoadError::Invalid(_))), "{result:?}");
+    }
+
+    #[test]
+    fn a_play_that_is_not_an_object_is_rejected() {
+        assert_invalid(r#"{"format": "jugge", "version": 4, "play": 5}"#);
+    }
+
+    #[test]
+    fn an_animation_that_is_not_an_object_is_rejected() {
+        assert_invalid(r#"{"format": "jugge", "version": 1, "play": {"animation": [1]}}"#);
+    }
+
+    #[test]
+    fn a_player_that_is_not_an_object_is_rejected() {
+        assert_invalid(
+            r#"{"format": "jugge", "version": 1, "play": {"animation": {"frames": [[3]]}}}"#,
+        );
+    }
+
+    #[test]
+    fn a_step_that_is_not_an_object_is_rejected() {
+        assert_invalid(
+            r#"{"format": "jugge", "version": 2, "play": {"animation": {"frames": [7]}}}"#,
+        );
+    }
+}
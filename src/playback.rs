@@ -0,0 +1,126 @@
+use egui::Ui;
+
+const SPEEDS: [f32; 6] = [0.25, 0.5, 0.75, 1.0, 1.5, 2.0];
+
+/// State of the transport bar that plays the animation
+pub struct Playback {
+    /// Show the animation at `time` instead of editing the current step
+    pub active: bool,
+    pub playing: bool,
+    /// Seconds into the animation
+    pub time: f32,
+    pub speed: f32,
+    pub looping: bool,
+}
+
+impl Default for Playback {
+    fn default() -> Self {
+        Self {
+            active: false,
+            playing: false,
+            time: 0.0,
+            speed: 1.0,
+            looping: false,
+        }
+    }
+}
+
+impl Playback {
+    /// Go back to editing
+    pub fn stop(&mut self) {
+        self.active = false;
+        self.playing = false;
+    }
+
+    fn play(&mut self, duration: f32) {
+        if !self.active || self.time >= duration {
+            self.time = 0.0;
+        }
+        self.active = true;
+        self.playing = true;
+    }
+
+    /// Moves time forward while playing
+    pub fn advance(&mut self, ctx: &egui::Context, duration: f32) {
+        if !(self.active && self.playing) {
+            return;
+        }
+
+        let dt = ctx.input(|i| i.stable_dt).min(0.1);
+        self.time += dt * self.speed;
+        if self.time >= duration {
+            if self.looping {
+                self.time %= duration;
+            } else {
+                self.time = duration;
+                self.playing = false;
+            }
+        }
+        ctx.request_repaint();
+    }
+
+    /// The transport bar. `step_starts` are the times each step starts at.
+    pub fn display(&mut self, ui: &mut Ui, duration: f32, step_starts: &[f32]) {
+        ui.horizontal(|ui| {
+            if ui.button("⏮").on_hover_text("Previous step").clicked() {
+                let prev = step_starts
+                    .iter()
+                    .rev()
+                    .find(|&&start| start < self.time - 1e-3)
+                    .copied();
+                self.time = prev.unwrap_or(0.0);
+                self.active = true;
+                self.playing = false;
+            }
+
+            if self.active && self.playing {
+                if ui.button("⏸").on_hover_text("Pause").clicked() {
+                    self.playing = false;
+                }
+            } else if ui.button("▶").on_hover_text("Play").clicked() {
+                self.play(duration);
+            }
+
+            if ui.button("⏭").on_hover_text("Next step").clicked() {
+                let next = step_starts
+                    .iter()
+                    .find(|&&start| start > self.time + 1e-3)
+                    .copied();
+                self.time = next.unwrap_or(duration);
+                self.active = true;
+                self.playing = false;
+            }
+
+            if ui
+                .add_enabled(self.active, egui::Button::new("⏹"))
+                .on_hover_text("Stop and edit")
+                .clicked()
+            {
+                self.stop();
+            }
+
+            let scrubber = ui.add(
+                egui::Slider::new(&mut self.time, 0.0..=duration)
+                    .show_value(false)
+                    .clamp_to_range(true),
+            );
+            if scrubber.dragged() || scrubber.changed() {
+                self.active = true;
+                self.playing = false;
+            }
+            ui.label(format!("{:.2} / {:.2} s", self.time, duration));
+
+            egui::ComboBox::from_id_source("playback speed")
+                .width(60.0)
+                .selected_text(format!("{}x", self.speed))
+                .show_ui(ui, |ui| {
+                    for speed in SPEEDS {
+                        ui.selectable_value(&mut self.speed, speed, format!("{speed}x"));
+                    }
+                });
+
+            ui.toggle_value(&mut self.looping, "🔁")
+                .on_hover_text("Loop");
+        });
+    }
+}
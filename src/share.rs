@@ -0,0 +1,114 @@
+//! Links that contain a whole play in the url fragment, e.g.
+//! `https://iverks.github.io/jugge/#play=<data>`.
+//!
+//! The data is the versioned json from [`save::to_json`], deflated and
+//! encoded as url safe base64.
+
+use std::io::{Read as _, Write as _};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+
+use crate::model::{save, Play};
+
+const HASH_PREFIX: &str = "#play=";
+
+/// Links larger than this are rejected before decompressing them
+const MAX_DATA_LEN: usize = 64 * 1024;
+
+/// A play opened from a link
+pub struct SharedPlay {
+    pub play: Play,
+}
+
+pub fn encode(play: &Play) -> String {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    encoder
+        .write_all(save::to_json(play).as_bytes())
+        .expect("writing to a vec should not fail");
+    let compressed = encoder.finish().expect("writing to a vec should not fail");
+    URL_SAFE_NO_PAD.encode(compressed)
+}
+
+pub fn decode(data: &str) -> Result<Play, String> {
+    if data.len() > MAX_DATA_LEN {
+        return Err("the link is too long".to_owned());
+    }
+    let compressed = URL_SAFE_NO_PAD
+        .decode(data)
+        .map_err(|err| format!("the link is not valid base64: {err}"))?;
+    let mut json = String::new();
+    DeflateDecoder::new(compressed.as_slice())
+        .take(16 * MAX_DATA_LEN as u64)
+        .read_to_string(&mut json)
+        .map_err(|err| format!("the link could not be decompressed: {err}"))?;
+    save::from_json(&json).map_err(|err| err.to_string())
+}
+
+/// Parses the fragment of a url, e.g. `window.location.hash`.
+/// Returns `Ok(None)` if the fragment does not contain a play.
+pub fn from_hash(hash: &str) -> Result<Option<SharedPlay>, String> {
+    match hash.strip_prefix(HASH_PREFIX) {
+        Some(data) => decode(data).map(|play| Some(SharedPlay { play })),
+        None => Ok(None),
+    }
+}
+
+/// A link to the app that opens `play`
+#[cfg(target_arch = "wasm32")]
+pub fn link(play: &Play) -> Option<String> {
+    let location = web_sys::window()?.location();
+    let origin = location.origin().ok()?;
+    let path = location.pathname().ok()?;
+    Some(format!("{origin}{path}{HASH_PREFIX}{}", encode(play)))
+}
+
+/// Removes the play from the url, so that reloading does not open it again
+#[cfg(target_arch = "wasm32")]
+pub fn clear_hash() {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let (Ok(path), Ok(history)) = (window.location().pathname(), window.history()) else {
+        return;
+    };
+    history
+        .replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&path))
+        .ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The data of a link to `json`, whatever it contains
+    fn data(json: &str) -> String {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(json.as_bytes()).unwrap();
+        URL_SAFE_NO_PAD.encode(encoder.finish().unwrap())
+    }
+
+    #[test]
+    fn a_shared_play_opens_the_same() {
+        let play = Play::default();
+        assert_eq!(decode(&encode(&play)).unwrap(), play);
+    }
+
+    #[test]
+    fn a_link_to_a_play_of_the_wrong_shape_is_rejected() {
+        for json in [
+            r#"{"version": 1, "animation": [1]}"#,
+            r#"{"version": 1, "animation": {"frames": [[3]]}}"#,
+            r#"{"version": 2, "animation": {"frames": [7]}}"#,
+        ] {
+            let hash = format!("{HASH_PREFIX}{}", data(json));
+            assert!(from_hash(&hash).is_err(), "{json}");
+        }
+    }
+
+    #[test]
+    fn a_broken_link_is_rejected() {
+        assert!(from_hash("#play=not base64!").is_err());
+        assert!(from_hash(&format!("{HASH_PREFIX}{}", data("not json"))).is_err());
+    }
+}